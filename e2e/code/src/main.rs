@@ -1,7 +1,7 @@
 use lambda_runtime::{Error, LambdaEvent, service_fn};
 use serde_json::Value;
 use serde_json::json;
-use secrets_manager_macro::build_secrets_struct;
+use secrets_manager_macro_runtime::build_secrets_struct;
 
 #[build_secrets_struct(envs = dev,prod)]
 struct SecretsManagerTestSecret {}
@@ -17,7 +17,7 @@ async fn flow() -> Result<Value, Error> {
     let env = std::env::var("ENV").unwrap_or_else(|_| "unknown".to_string());
     println!("Invoked test lambda for environment {}", env);
 
-    let secrets = SecretsManagerTestSecret::new().await;
+    let secrets = SecretsManagerTestSecret::get().await;
 
     Ok(json!({
         "firstValue": secrets.firstKey.as_ref(),
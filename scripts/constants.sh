@@ -9,5 +9,29 @@ SECRET_VALUE_FOR_PREFIX_PROD='{ "firstKey": "prodValue", "secondKey": "secondPro
 SECRET_NAME_WITHOUT_PREFIX="NoPrefixSecret"
 SECRET_VALUE_FOR_NO_PREFIX='{ "thirdKey": "thirdValue" }'
 
+OTHER_SECRET_NAME_WITHOUT_PREFIX="OtherNoPrefixSecret"
+OTHER_SECRET_VALUE_FOR_NO_PREFIX='{ "fourthKey": "fourthValue" }'
+
+SECRET_NAME_WITH_SUFFIX_DEV="secrets-manager-test/pattern-secret/dev"
+SECRET_VALUE_FOR_SUFFIX_DEV='{ "fifthKey": "fifthValue" }'
+
+SECRET_NAME_WITH_SUFFIX_PROD="secrets-manager-test/pattern-secret/prod"
+SECRET_VALUE_FOR_SUFFIX_PROD='{ "fifthKey": "fifthProdValue" }'
+
 INVALID_JSON_SECRET="InvalidSecret"
 INVALID_JSON_VALUE='"firstKey": "prodValue"'
+
+PARAMETER_NAME_WITH_PREFIX_DEV="/secrets-manager-test-parameters/dev/firstParameter"
+PARAMETER_VALUE_FOR_PREFIX_DEV="firstParameterValue"
+
+PARAMETER_NAME_WITH_PREFIX_PROD="/secrets-manager-test-parameters/prod/firstParameter"
+PARAMETER_VALUE_FOR_PREFIX_PROD="prodParameterValue"
+
+PARAMETER_NAME_WITHOUT_PREFIX="/secrets-manager-test-no-prefix-parameters/nested/second-parameter"
+PARAMETER_VALUE_FOR_NO_PREFIX="secondParameterValue"
+
+SECURE_PARAMETER_NAME="/secrets-manager-test-no-prefix-parameters/password"
+SECURE_PARAMETER_VALUE="passwordValue"
+
+LIST_PARAMETER_NAME="/secrets-manager-test-no-prefix-parameters/hosts"
+LIST_PARAMETER_VALUE="first-host,second-host"
@@ -14,4 +14,18 @@ function force_delete_secret() {
 force_delete_secret "$SECRET_NAME_WITH_PREFIX_DEV"
 force_delete_secret "$SECRET_NAME_WITH_PREFIX_PROD"
 force_delete_secret "$SECRET_NAME_WITHOUT_PREFIX"
+force_delete_secret "$OTHER_SECRET_NAME_WITHOUT_PREFIX"
+force_delete_secret "$SECRET_NAME_WITH_SUFFIX_DEV"
+force_delete_secret "$SECRET_NAME_WITH_SUFFIX_PROD"
 force_delete_secret "$INVALID_JSON_SECRET"
+
+function delete_parameter() {
+  echo "Deleting parameter with name $1"
+  aws ssm delete-parameter --name "$1" 2>&1 > /dev/null
+}
+
+delete_parameter "$PARAMETER_NAME_WITH_PREFIX_DEV"
+delete_parameter "$PARAMETER_NAME_WITH_PREFIX_PROD"
+delete_parameter "$PARAMETER_NAME_WITHOUT_PREFIX"
+delete_parameter "$SECURE_PARAMETER_NAME"
+delete_parameter "$LIST_PARAMETER_NAME"
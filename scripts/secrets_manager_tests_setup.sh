@@ -14,4 +14,20 @@ function create_secret_if_not_exist() {
 create_secret_if_not_exist "${SECRET_NAME_WITH_PREFIX_DEV}" "${SECRET_VALUE_FOR_PREFIX_DEV}"
 create_secret_if_not_exist "${SECRET_NAME_WITH_PREFIX_PROD}" "${SECRET_VALUE_FOR_PREFIX_PROD}"
 create_secret_if_not_exist "${SECRET_NAME_WITHOUT_PREFIX}" "${SECRET_VALUE_FOR_NO_PREFIX}"
+create_secret_if_not_exist "${OTHER_SECRET_NAME_WITHOUT_PREFIX}" "${OTHER_SECRET_VALUE_FOR_NO_PREFIX}"
+create_secret_if_not_exist "${SECRET_NAME_WITH_SUFFIX_DEV}" "${SECRET_VALUE_FOR_SUFFIX_DEV}"
+create_secret_if_not_exist "${SECRET_NAME_WITH_SUFFIX_PROD}" "${SECRET_VALUE_FOR_SUFFIX_PROD}"
 create_secret_if_not_exist "${INVALID_JSON_SECRET}" "${INVALID_JSON_VALUE}"
+
+function create_parameter_if_not_exist() {
+  if ! aws ssm get-parameter --name "$1" 2>&1 > /dev/null; then
+    echo "No parameter with name $1 yet - creating it with expected value"
+    aws ssm put-parameter --name "$1" --value "$2" --type "$3" 2>&1 > /dev/null
+  fi
+}
+
+create_parameter_if_not_exist "${PARAMETER_NAME_WITH_PREFIX_DEV}" "${PARAMETER_VALUE_FOR_PREFIX_DEV}" String
+create_parameter_if_not_exist "${PARAMETER_NAME_WITH_PREFIX_PROD}" "${PARAMETER_VALUE_FOR_PREFIX_PROD}" String
+create_parameter_if_not_exist "${PARAMETER_NAME_WITHOUT_PREFIX}" "${PARAMETER_VALUE_FOR_NO_PREFIX}" String
+create_parameter_if_not_exist "${SECURE_PARAMETER_NAME}" "${SECURE_PARAMETER_VALUE}" SecureString
+create_parameter_if_not_exist "${LIST_PARAMETER_NAME}" "${LIST_PARAMETER_VALUE}" StringList
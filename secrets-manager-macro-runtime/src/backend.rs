@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use aws_sdk_secretsmanager::Client;
+
+use crate::config::load_config;
+use crate::errors::Error;
+use crate::lambda_extension::LambdaExtension;
+use crate::local_files::LocalFiles;
+use crate::mounted_dir::MountedDir;
+use crate::secrets::{Secret, Version};
+use crate::source::SecretSource;
+use crate::vault::Vault;
+
+/// Where the generated code retrieves secrets from at runtime
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    /// The Lambda extension when running in a Lambda that has it, the SDK otherwise
+    Auto,
+    Sdk,
+    LambdaExtension,
+    /// HashiCorp Vault KV v2, at `VAULT_ADDR`
+    Vault,
+    /// One file per key, as mounted by the Secrets Store CSI driver
+    MountedDir,
+}
+
+/// Retrieves secrets from the chosen backend
+pub enum SecretsClient {
+    Sdk(Client),
+    LambdaExtension(LambdaExtension),
+    LocalFiles(LocalFiles),
+    MountedDir(MountedDir),
+    /// Without `VAULT_ADDR` and `VAULT_TOKEN`, every retrieval returns a `MissingEnv` error
+    Vault(Option<Vault>),
+}
+
+impl SecretsClient {
+    /// `retries` and `timeout` only apply to the SDK.
+    /// `SECRETS_SOURCE` overrides the backend at runtime: `file` uses local files, `mounted_dir` a mounted directory
+    pub async fn new(backend: Backend, retries: Option<u32>, timeout: Option<Duration>) -> Self {
+        match std::env::var("SECRETS_SOURCE").as_deref() {
+            Ok("file") => return SecretsClient::LocalFiles(LocalFiles::from_env()),
+            Ok("mounted_dir") => return SecretsClient::MountedDir(MountedDir::from_env()),
+            _ => {}
+        }
+
+        match backend {
+            Backend::LambdaExtension => SecretsClient::LambdaExtension(LambdaExtension::from_env()),
+            Backend::Vault => SecretsClient::Vault(Vault::from_env()),
+            Backend::MountedDir => SecretsClient::MountedDir(MountedDir::from_env()),
+            Backend::Auto if is_lambda() => {
+                let extension = LambdaExtension::from_env();
+                if extension.is_running().await {
+                    SecretsClient::LambdaExtension(extension)
+                } else {
+                    SecretsClient::Sdk(Client::new(&load_config(retries, timeout).await))
+                }
+            }
+            Backend::Auto | Backend::Sdk => SecretsClient::Sdk(Client::new(&load_config(retries, timeout).await)),
+        }
+    }
+
+    /// Name of the backend, to tell apart cached secrets with the same name
+    pub fn name(&self) -> &'static str {
+        match self {
+            SecretsClient::Sdk(_) => "sdk",
+            SecretsClient::LambdaExtension(_) => "lambda_extension",
+            SecretsClient::LocalFiles(_) => "local_files",
+            SecretsClient::MountedDir(_) => "mounted_dir",
+            SecretsClient::Vault(_) => "vault",
+        }
+    }
+}
+
+#[async_trait]
+impl SecretSource for SecretsClient {
+    async fn fetch(&self, secret_name: &str) -> Result<HashMap<String, String>, Error> {
+        Ok(self.fetch_version(secret_name, &Version::Current).await?.into_values())
+    }
+
+    async fn fetch_version(&self, secret_name: &str, version: &Version) -> Result<Secret, Error> {
+        match self {
+            SecretsClient::Sdk(client) => client.fetch_version(secret_name, version).await,
+            SecretsClient::LambdaExtension(extension) => extension.fetch_version(secret_name, version).await,
+            SecretsClient::LocalFiles(local_files) => local_files.fetch_version(secret_name, version).await,
+            SecretsClient::MountedDir(mounted_dir) => mounted_dir.fetch_version(secret_name, version).await,
+            SecretsClient::Vault(Some(vault)) => vault.fetch_version(secret_name, version).await,
+            SecretsClient::Vault(None) => Err(Error::MissingEnv(vec!["VAULT_ADDR".to_string(), "VAULT_TOKEN".to_string()])),
+        }
+    }
+}
+
+fn is_lambda() -> bool {
+    std::env::var("AWS_LAMBDA_FUNCTION_NAME").is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn new_should_use_lambda_extension_when_asked() {
+        let actual = SecretsClient::new(Backend::LambdaExtension, None, None).await;
+
+        assert!(matches!(actual, SecretsClient::LambdaExtension(_)));
+    }
+
+    #[tokio::test]
+    async fn new_should_use_mounted_dir_when_asked() {
+        let actual = SecretsClient::new(Backend::MountedDir, None, None).await;
+
+        assert!(matches!(actual, SecretsClient::MountedDir(_)));
+    }
+
+    #[tokio::test]
+    async fn new_should_use_sdk_outside_of_lambda() {
+        std::env::remove_var("AWS_LAMBDA_FUNCTION_NAME");
+
+        let actual = SecretsClient::new(Backend::Auto, None, None).await;
+
+        assert!(matches!(actual, SecretsClient::Sdk(_)));
+    }
+}
@@ -0,0 +1,51 @@
+use std::future::Future;
+
+use crate::errors::Error;
+
+/// Runs the future on an internal current-thread runtime.
+/// Starting a runtime from within a runtime panics, so this moves to another thread when called from within one
+pub fn block_on<F, Fut, T>(create_future: F) -> Result<T, Error>
+    where F: FnOnce() -> Fut + Send,
+          Fut: Future<Output=Result<T, Error>>,
+          T: Send {
+    let run = || {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| Error::Runtime(e.to_string()))?
+            .block_on(create_future())
+    };
+
+    if tokio::runtime::Handle::try_current().is_ok() {
+        std::thread::scope(|scope| scope.spawn(run).join())
+            .unwrap_or_else(|e| std::panic::resume_unwind(e))
+    } else {
+        run()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_on_should_run_future_outside_of_runtime() {
+        let actual = block_on(|| async { Ok(1) });
+
+        assert_eq!(actual.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn block_on_should_run_future_inside_of_runtime() {
+        let actual = block_on(|| async { Ok(1) });
+
+        assert_eq!(actual.unwrap(), 1);
+    }
+
+    #[test]
+    fn block_on_should_return_error_of_future() {
+        let actual: Result<(), Error> = block_on(|| async { Err(Error::MissingKey("key".to_string())) });
+
+        assert!(actual.is_err());
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use futures::lock::Mutex as AsyncMutex;
+
+use crate::errors::Error;
+use crate::secrets::{Secret, Version};
+
+static GLOBAL: SecretCache = SecretCache::new();
+
+// locked while the secret is being retrieved, so concurrent callers wait for that retrieval instead of starting their own
+type Slot = Arc<AsyncMutex<Option<CachedSecret>>>;
+
+// backend, secret name and version
+type Key = (&'static str, String, Version);
+
+struct CachedSecret {
+    secret: Secret,
+    cached_at: Instant,
+    ttl: Duration,
+}
+
+/// In-process cache of secrets, keyed by backend, secret name and version.
+/// The global instance is shared by all generated structs with a `cache_ttl_secs`
+pub struct SecretCache {
+    entries: Mutex<BTreeMap<Key, Slot>>,
+}
+
+impl SecretCache {
+    pub const fn new() -> Self {
+        SecretCache {
+            entries: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn global() -> &'static SecretCache {
+        &GLOBAL
+    }
+
+    /// Returns the cached secret when it is younger than `ttl`, otherwise retrieves and caches it.
+    /// Concurrent callers for the same secret and version from the same backend share a single retrieval
+    pub async fn get_or_fetch<F, Fut>(&self, backend: &'static str, secret_name: &str, version: &Version, ttl: Duration, fetch: F) -> Result<Secret, Error>
+        where F: FnOnce() -> Fut,
+              Fut: Future<Output=Result<Secret, Error>> {
+        let slot = self.slot(backend, secret_name, version);
+        let mut cached = slot.lock().await;
+
+        if let Some(fresh) = cached.as_ref().filter(|c| c.cached_at.elapsed() < ttl) {
+            return Ok(fresh.secret.clone());
+        }
+
+        let secret = fetch().await?;
+        *cached = Some(CachedSecret {
+            secret: secret.clone(),
+            cached_at: Instant::now(),
+            ttl,
+        });
+        Ok(secret)
+    }
+
+    /// Removes all versions of the given secret, from every backend
+    pub fn invalidate(&self, secret_name: &str) {
+        self.lock().retain(|(_, name, _), _| name != secret_name);
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    // separate function, so the lock of the map is never held across an await
+    fn slot(&self, backend: &'static str, secret_name: &str, version: &Version) -> Slot {
+        let key = (backend, secret_name.to_string(), version.clone());
+        let mut entries = self.lock();
+
+        if let Some(slot) = entries.get(&key) {
+            return slot.clone();
+        }
+        // only when adding a slot, so the map does not keep growing with secrets that are no longer retrieved
+        entries.retain(|_, slot| is_in_use_or_fresh(slot));
+        entries.entry(key).or_default().clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<Key, Slot>> {
+        // the map stays consistent even when another thread panicked
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+// a slot that is shared outside of the map belongs to a retrieval that is waiting or in progress
+fn is_in_use_or_fresh(slot: &Slot) -> bool {
+    if Arc::strong_count(slot) > 1 {
+        return true;
+    }
+    match slot.try_lock() {
+        Some(cached) => matches!(cached.as_ref(), Some(c) if c.cached_at.elapsed() < c.ttl),
+        None => true,
+    }
+}
+
+impl Default for SecretCache {
+    fn default() -> Self {
+        SecretCache::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    fn secret(version: &str) -> Secret {
+        Secret::new(HashMap::new(), Some(version.to_string()))
+    }
+
+    #[tokio::test]
+    async fn get_or_fetch_should_only_fetch_once_within_ttl() {
+        let cache = SecretCache::new();
+        let calls = AtomicUsize::new(0);
+        let fetch = || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Ok(secret("v1"))
+        };
+
+        let first = cache.get_or_fetch("sdk", "example", &Version::Current, Duration::from_secs(60), fetch).await.unwrap();
+        let second = cache.get_or_fetch("sdk", "example", &Version::Current, Duration::from_secs(60), fetch).await.unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(first.version_id(), second.version_id());
+    }
+
+    #[tokio::test]
+    async fn get_or_fetch_should_share_concurrent_fetches() {
+        let cache = SecretCache::new();
+        let calls = AtomicUsize::new(0);
+        let fetch = || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Ok(secret("v1"))
+        };
+
+        let actual = futures::future::join_all((0..10).map(|_| cache.get_or_fetch("sdk", "example", &Version::Current, Duration::from_secs(60), fetch))).await;
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(actual.iter().all(|s| s.as_ref().unwrap().version_id() == Some("v1")));
+    }
+
+    #[tokio::test]
+    async fn get_or_fetch_should_fetch_again_after_ttl() {
+        let cache = SecretCache::new();
+
+        cache.get_or_fetch("sdk", "example", &Version::Current, Duration::ZERO, || async { Ok(secret("v1")) }).await.unwrap();
+        let actual = cache.get_or_fetch("sdk", "example", &Version::Current, Duration::ZERO, || async { Ok(secret("v2")) }).await.unwrap();
+
+        assert_eq!(actual.version_id(), Some("v2"));
+    }
+
+    #[tokio::test]
+    async fn get_or_fetch_should_cache_versions_separately() {
+        let cache = SecretCache::new();
+
+        cache.get_or_fetch("sdk", "example", &Version::Current, Duration::from_secs(60), || async { Ok(secret("v2")) }).await.unwrap();
+        let actual = cache.get_or_fetch("sdk", "example", &Version::Stage("AWSPREVIOUS".to_string()), Duration::from_secs(60), || async { Ok(secret("v1")) }).await.unwrap();
+
+        assert_eq!(actual.version_id(), Some("v1"));
+    }
+
+    #[tokio::test]
+    async fn get_or_fetch_should_not_cache_errors() {
+        let cache = SecretCache::new();
+
+        let failed = cache.get_or_fetch("sdk", "example", &Version::Current, Duration::from_secs(60), || async { Err(Error::Aws("unavailable".to_string())) }).await;
+        let actual = cache.get_or_fetch("sdk", "example", &Version::Current, Duration::from_secs(60), || async { Ok(secret("v1")) }).await.unwrap();
+
+        assert!(failed.is_err());
+        assert_eq!(actual.version_id(), Some("v1"));
+    }
+
+    #[tokio::test]
+    async fn invalidate_should_remove_secret_from_cache() {
+        let cache = SecretCache::new();
+        cache.get_or_fetch("sdk", "example", &Version::Current, Duration::from_secs(60), || async { Ok(secret("v1")) }).await.unwrap();
+
+        cache.invalidate("example");
+        let actual = cache.get_or_fetch("sdk", "example", &Version::Current, Duration::from_secs(60), || async { Ok(secret("v2")) }).await.unwrap();
+
+        assert_eq!(actual.version_id(), Some("v2"));
+    }
+
+    #[tokio::test]
+    async fn get_or_fetch_should_cache_backends_separately() {
+        let cache = SecretCache::new();
+
+        cache.get_or_fetch("sdk", "example", &Version::Current, Duration::from_secs(60), || async { Ok(secret("v1")) }).await.unwrap();
+        let actual = cache.get_or_fetch("vault", "example", &Version::Current, Duration::from_secs(60), || async { Ok(secret("v2")) }).await.unwrap();
+
+        assert_eq!(actual.version_id(), Some("v2"));
+    }
+
+    #[tokio::test]
+    async fn get_or_fetch_should_evict_expired_secrets() {
+        let cache = SecretCache::new();
+        cache.get_or_fetch("sdk", "expired", &Version::Current, Duration::ZERO, || async { Ok(secret("v1")) }).await.unwrap();
+        let failed = cache.get_or_fetch("sdk", "failed", &Version::Current, Duration::from_secs(60), || async { Err(Error::Aws("unavailable".to_string())) }).await;
+        cache.get_or_fetch("sdk", "fresh", &Version::Current, Duration::from_secs(60), || async { Ok(secret("v1")) }).await.unwrap();
+
+        cache.get_or_fetch("sdk", "example", &Version::Current, Duration::from_secs(60), || async { Ok(secret("v1")) }).await.unwrap();
+
+        let actual: Vec<String> = cache.lock().keys().map(|(_, name, _)| name.clone()).collect();
+        assert!(failed.is_err());
+        assert_eq!(actual, vec!["example".to_string(), "fresh".to_string()]);
+    }
+}
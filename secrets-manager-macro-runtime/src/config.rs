@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use aws_config::retry::RetryConfig;
+use aws_config::timeout::TimeoutConfig;
+use aws_config::SdkConfig;
+
+/// Loads the AWS config from the environment.
+/// With `retries`, throttling and other retryable errors are retried with exponential backoff and jitter.
+/// With `timeout`, retrieval (including retries) gives up after that duration
+pub async fn load_config(retries: Option<u32>, timeout: Option<Duration>) -> SdkConfig {
+    let mut loader = aws_config::from_env();
+
+    if let Some(retries) = retries {
+        loader = loader.retry_config(retry_config(retries));
+    }
+    if let Some(timeout) = timeout {
+        loader = loader.timeout_config(timeout_config(timeout));
+    }
+    loader.load().await
+}
+
+fn retry_config(retries: u32) -> RetryConfig {
+    // the first attempt is not a retry
+    RetryConfig::standard().with_max_attempts(retries.saturating_add(1))
+}
+
+fn timeout_config(timeout: Duration) -> TimeoutConfig {
+    TimeoutConfig::builder()
+        .operation_timeout(timeout)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_config_should_add_first_attempt_to_retries() {
+        let actual = retry_config(5);
+
+        assert_eq!(actual.max_attempts(), 6);
+    }
+
+    #[test]
+    fn retry_config_should_not_overflow() {
+        let actual = retry_config(u32::MAX);
+
+        assert_eq!(actual.max_attempts(), u32::MAX);
+    }
+
+    #[test]
+    fn timeout_config_should_set_operation_timeout() {
+        let actual = timeout_config(Duration::from_millis(2000));
+
+        assert_eq!(actual.operation_timeout(), Some(Duration::from_millis(2000)));
+    }
+}
@@ -0,0 +1,61 @@
+use std::error::Error as StdError;
+use std::fmt::{Display, Formatter};
+
+/// Error returned by the generated `try_` constructors
+#[derive(Debug)]
+pub enum Error {
+    MissingEnv(Vec<String>),
+    NotFound(String),
+    Aws(String),
+    /// Vault could not be reached or refused the request
+    Vault(String),
+    Json(serde_json::Error),
+    MissingKey(String),
+    Runtime(String),
+}
+
+/// The kind of an `Error`, without any details
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    MissingEnv,
+    NotFound,
+    Aws,
+    Vault,
+    Json,
+    MissingKey,
+    Runtime,
+}
+
+impl Error {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::MissingEnv(_) => ErrorKind::MissingEnv,
+            Error::NotFound(_) => ErrorKind::NotFound,
+            Error::Aws(_) => ErrorKind::Aws,
+            Error::Vault(_) => ErrorKind::Vault,
+            Error::Json(_) => ErrorKind::Json,
+            Error::MissingKey(_) => ErrorKind::MissingKey,
+            Error::Runtime(_) => ErrorKind::Runtime,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::MissingEnv(variables) => write!(f, "expected one of these environment variables to be present: {}", variables.join(",")),
+            Error::NotFound(e) | Error::Aws(e) | Error::Vault(e) => f.write_str(e),
+            Error::Json(e) => write!(f, "could not parse the secret value as JSON: {}", e),
+            Error::MissingKey(key) => write!(f, "expected key {} to be present in the secret", key),
+            Error::Runtime(e) => write!(f, "could not start a runtime: {}", e),
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(value: serde_json::Error) -> Self {
+        Error::Json(value)
+    }
+}
+
+impl StdError for Error {}
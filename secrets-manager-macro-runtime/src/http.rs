@@ -0,0 +1,103 @@
+use hyper::body::Bytes;
+use hyper::client::HttpConnector;
+use hyper::{Body, Client, Request, StatusCode};
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+
+/// Sends a GET request with the given header, returning the status and body of the response
+pub(crate) async fn get(uri: &str, header: (&str, &str)) -> Result<(StatusCode, Bytes), String> {
+    let request = Request::get(uri)
+        .header(header.0, header.1)
+        .body(Body::empty())
+        .map_err(|e| e.to_string())?;
+
+    let response = client()
+        .request(request)
+        .await
+        .map_err(|e| e.to_string())?;
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok((status, body))
+}
+
+// the Lambda extension only speaks http, Vault usually https
+fn client() -> Client<HttpsConnector<HttpConnector>> {
+    let connector = HttpsConnectorBuilder::new()
+        .with_native_roots()
+        .https_or_http()
+        .enable_http1()
+        .build();
+    Client::builder().build(connector)
+}
+
+/// Percent-encodes everything except unreserved characters, so names like /dev/secret survive a query string
+pub(crate) fn encode(value: &str) -> String {
+    value.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Percent-encodes every segment of a path, keeping the slashes between them
+pub(crate) fn encode_path(path: &str) -> String {
+    path.split('/')
+        .map(encode)
+        .collect::<Vec<String>>()
+        .join("/")
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::io::{Read, Write};
+    use std::net::{SocketAddr, TcpListener};
+    use std::thread::JoinHandle;
+
+    use super::*;
+
+    /// Answers a single request with the given status and body, and returns the request it received
+    pub(crate) fn stand_in(status: &str, body: &str) -> (SocketAddr, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let response = format!("HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", status, body.len(), body);
+
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let read = stream.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..read]);
+            }
+            stream.write_all(response.as_bytes()).unwrap();
+            String::from_utf8(request).unwrap()
+        });
+        (address, handle)
+    }
+
+    #[tokio::test]
+    async fn get_should_send_header_and_return_status_and_body() {
+        let (address, handle) = stand_in("404 Not Found", "nothing here");
+
+        let (status, body) = get(&format!("http://{}/some/path", address), ("X-Some-Header", "a-value")).await.unwrap();
+        let request = handle.join().unwrap().to_lowercase();
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(&body[..], b"nothing here");
+        assert!(request.starts_with("get /some/path http/1.1"));
+        assert!(request.contains("x-some-header: a-value"));
+    }
+
+    #[test]
+    fn encode_should_escape_reserved_characters() {
+        assert_eq!(encode("/dev/some secret+1"), "%2Fdev%2Fsome%20secret%2B1");
+        assert_eq!(encode("Secret-name_1.2~"), "Secret-name_1.2~");
+    }
+
+    #[test]
+    fn encode_path_should_keep_slashes_between_segments() {
+        assert_eq!(encode_path("team/some secret?"), "team/some%20secret%3F");
+    }
+}
@@ -0,0 +1,96 @@
+use std::future::Future;
+use std::time::Instant;
+
+use crate::errors::Error;
+use crate::observer::{self, FetchEvent};
+use crate::secrets::Secret;
+
+/// Describes a retrieval of a secret by a generated struct
+pub struct FetchContext<'a> {
+    pub struct_name: &'static str,
+    pub secret_name: &'a str,
+    pub env: Option<&'a str>,
+}
+
+/// Awaits the retrieval of a secret and notifies the registered observer (if any).
+/// With the `tracing` feature, this happens inside a span that records the secret name, env, version id, duration and outcome.
+/// Values of the secret are never recorded
+pub async fn instrumented<F>(context: &FetchContext<'_>, fetch: F) -> Result<Secret, Error>
+    where F: Future<Output=Result<Secret, Error>> {
+    #[cfg(feature = "tracing")]
+    let span = tracing::info_span!(
+        "secret_fetch",
+        struct_name = context.struct_name,
+        secret_name = context.secret_name,
+        env = context.env,
+        version_id = tracing::field::Empty,
+        duration_ms = tracing::field::Empty,
+        outcome = tracing::field::Empty,
+    );
+
+    let start = Instant::now();
+    #[cfg(feature = "tracing")]
+    let result = tracing::Instrument::instrument(fetch, span.clone()).await;
+    #[cfg(not(feature = "tracing"))]
+    let result = fetch.await;
+    let duration = start.elapsed();
+
+    #[cfg(feature = "tracing")]
+    record(&span, &result, duration);
+    observer::notify(&FetchEvent {
+        struct_name: context.struct_name,
+        secret_name: context.secret_name,
+        duration,
+        error: result.as_ref().err().map(|e| e.kind()),
+    });
+
+    result
+}
+
+#[cfg(feature = "tracing")]
+fn record(span: &tracing::Span, result: &Result<Secret, Error>, duration: std::time::Duration) {
+    span.record("duration_ms", duration.as_millis() as u64);
+
+    match result {
+        Ok(secret) => {
+            span.record("outcome", "success");
+            if let Some(version_id) = secret.version_id() {
+                span.record("version_id", version_id);
+            }
+            tracing::info!(parent: span, "retrieved secret");
+        }
+        Err(e) => {
+            span.record("outcome", "error");
+            tracing::warn!(parent: span, error = %e, "could not retrieve secret");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn context() -> FetchContext<'static> {
+        FetchContext {
+            struct_name: "ExampleSecret",
+            secret_name: "/dev/example-secret",
+            env: Some("dev"),
+        }
+    }
+
+    #[tokio::test]
+    async fn instrumented_should_return_result_of_fetch() {
+        let actual = instrumented(&context(), async { Ok(Secret::new(HashMap::new(), Some("a-version".to_string()))) }).await;
+
+        assert_eq!(actual.unwrap().version_id(), Some("a-version"));
+    }
+
+    #[tokio::test]
+    async fn instrumented_should_return_error_of_fetch() {
+        let actual = instrumented(&context(), async { Err(Error::Aws("unavailable".to_string())) }).await;
+
+        assert!(actual.is_err());
+    }
+}
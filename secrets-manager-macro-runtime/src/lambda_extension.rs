@@ -0,0 +1,205 @@
+use std::collections::{BTreeMap, HashMap};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use hyper::StatusCode;
+use tokio::sync::OnceCell;
+
+use crate::errors::Error;
+use crate::http::{self, encode};
+use crate::secrets::{Secret, Version};
+use crate::source::SecretSource;
+
+const DEFAULT_PORT: u16 = 2773;
+const PORT_VARIABLE: &str = "PARAMETERS_SECRETS_EXTENSION_HTTP_PORT";
+const TOKEN_HEADER: &str = "X-Aws-Parameters-Secrets-Token";
+// the extension runs next to the function, so it answers almost immediately when it is present
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(50);
+
+// whether the extension runs does not change during the lifetime of a function, so every address is only probed once.
+// Concurrent first callers wait for the same probe
+static PROBES: Mutex<BTreeMap<SocketAddr, Arc<OnceCell<bool>>>> = Mutex::new(BTreeMap::new());
+
+/// Retrieves secrets through the AWS Parameters and Secrets Lambda Extension, which caches them for you
+pub struct LambdaExtension {
+    address: SocketAddr,
+    token: String,
+}
+
+impl LambdaExtension {
+    pub fn new(address: SocketAddr, token: &str) -> Self {
+        LambdaExtension {
+            address,
+            token: token.to_string(),
+        }
+    }
+
+    /// The extension on its default (or configured) port, authenticated with the session token of the function
+    pub fn from_env() -> Self {
+        let port = std::env::var(PORT_VARIABLE).ok()
+            .and_then(|p| p.parse().ok())
+            .unwrap_or(DEFAULT_PORT);
+        let token = std::env::var("AWS_SESSION_TOKEN").unwrap_or_default();
+        LambdaExtension::new(SocketAddr::from(([127, 0, 0, 1], port)), &token)
+    }
+
+    /// Whether the extension accepts connections. Only the first call per process (and address) connects to it
+    pub async fn is_running(&self) -> bool {
+        let probe_of_address = PROBES.lock().unwrap_or_else(|e| e.into_inner())
+            .entry(self.address)
+            .or_default()
+            .clone();
+        *probe_of_address.get_or_init(|| probe(self.address)).await
+    }
+}
+
+#[async_trait]
+impl SecretSource for LambdaExtension {
+    async fn fetch(&self, secret_name: &str) -> Result<HashMap<String, String>, Error> {
+        Ok(self.fetch_version(secret_name, &Version::Current).await?.into_values())
+    }
+
+    async fn fetch_version(&self, secret_name: &str, version: &Version) -> Result<Secret, Error> {
+        let (status, body) = http::get(&self.secret_uri(secret_name, version), (TOKEN_HEADER, &self.token))
+            .await
+            .map_err(|e| Error::Aws(format!("could not reach the Lambda extension for secret with name {}: {}", secret_name, e)))?;
+
+        match status {
+            StatusCode::OK => secret_from_response(&body),
+            StatusCode::BAD_REQUEST if is_not_found(&body) => Err(Error::NotFound(format!(
+                "could not find secret with name {} and version {:?}",
+                secret_name,
+                version
+            ))),
+            _ => Err(Error::Aws(format!(
+                "could not get secret with name {} from the Lambda extension (status {})",
+                secret_name,
+                status
+            ))),
+        }
+    }
+}
+
+impl LambdaExtension {
+    fn secret_uri(&self, secret_name: &str, version: &Version) -> String {
+        let version_parameter = match version {
+            Version::Current => "".to_string(),
+            Version::Stage(stage) => format!("&versionStage={}", encode(stage)),
+            Version::Id(id) => format!("&versionId={}", encode(id)),
+        };
+        format!("http://{}/secretsmanager/get?secretId={}{}", self.address, encode(secret_name), version_parameter)
+    }
+}
+
+// the extension answers with the same JSON as GetSecretValue
+fn secret_from_response(body: &[u8]) -> Result<Secret, Error> {
+    let output: serde_json::Value = serde_json::from_slice(body)?;
+    let content = output.get("SecretString")
+        .and_then(|v| v.as_str())
+        .unwrap_or("{}");
+    let values = serde_json::from_str(content)?;
+    let version_id = output.get("VersionId")
+        .and_then(|v| v.as_str())
+        .map(|v| v.to_string());
+    Ok(Secret::new(values, version_id))
+}
+
+// the extension passes on the error of GetSecretValue, with its type in `__type` (optionally after a namespace and `#`)
+fn is_not_found(body: &[u8]) -> bool {
+    serde_json::from_slice::<serde_json::Value>(body).ok()
+        .and_then(|error| error.get("__type")?.as_str().map(|t| t.rsplit('#').next() == Some("ResourceNotFoundException")))
+        .unwrap_or(false)
+}
+
+async fn probe(address: SocketAddr) -> bool {
+    matches!(tokio::time::timeout(CONNECT_TIMEOUT, tokio::net::TcpStream::connect(address)).await, Ok(Ok(_)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use crate::http::tests::stand_in;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn fetch_version_should_retrieve_secret_with_token() {
+        let body = r#"{ "Name": "/dev/secret", "SecretString": "{ \"key1\": \"value1\" }", "VersionId": "a-version" }"#;
+        let (address, handle) = stand_in("200 OK", body);
+        let extension = LambdaExtension::new(address, "a-token");
+
+        let actual = extension.fetch_version("/dev/secret", &Version::Current).await.unwrap();
+        let request = handle.join().unwrap().to_lowercase();
+
+        assert_eq!(actual.get("key1").unwrap(), "value1");
+        assert_eq!(actual.version_id(), Some("a-version"));
+        assert!(request.starts_with("get /secretsmanager/get?secretid=%2fdev%2fsecret http/1.1"));
+        assert!(request.contains("x-aws-parameters-secrets-token: a-token"));
+    }
+
+    #[tokio::test]
+    async fn fetch_version_should_return_not_found_for_unknown_secret() {
+        let body = r#"{ "__type": "ResourceNotFoundException", "message": "Secrets Manager can't find the specified secret." }"#;
+        let (address, handle) = stand_in("400 Bad Request", body);
+        let extension = LambdaExtension::new(address, "a-token");
+
+        let actual = extension.fetch_version("unknown", &Version::Stage("AWSPREVIOUS".to_string())).await;
+        let request = handle.join().unwrap();
+
+        assert!(matches!(actual, Err(Error::NotFound(_))));
+        assert!(request.contains("secretId=unknown&versionStage=AWSPREVIOUS"));
+    }
+
+    #[tokio::test]
+    async fn fetch_version_should_not_treat_message_mentioning_not_found_as_not_found() {
+        let body = r#"{ "__type": "AccessDeniedException", "message": "not authorized, no ResourceNotFoundException here" }"#;
+        let (address, handle) = stand_in("400 Bad Request", body);
+        let extension = LambdaExtension::new(address, "a-token");
+
+        let actual = extension.fetch_version("secret", &Version::Current).await;
+        handle.join().unwrap();
+
+        assert!(matches!(actual, Err(Error::Aws(_))));
+    }
+
+    #[test]
+    fn is_not_found_should_accept_namespaced_type() {
+        assert!(is_not_found(br#"{ "__type": "com.amazonaws.secretsmanager#ResourceNotFoundException" }"#));
+        assert!(!is_not_found(b"ResourceNotFoundException"));
+    }
+
+    #[tokio::test]
+    async fn is_running_should_detect_listening_extension() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let extension = LambdaExtension::new(listener.local_addr().unwrap(), "a-token");
+
+        assert!(extension.is_running().await);
+        drop(listener);
+    }
+
+    #[tokio::test]
+    async fn is_running_should_only_probe_once() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let extension = LambdaExtension::new(listener.local_addr().unwrap(), "a-token");
+
+        assert!(extension.is_running().await);
+        drop(listener);
+        assert!(extension.is_running().await);
+    }
+
+    #[tokio::test]
+    async fn is_running_should_probe_once_for_concurrent_callers() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let extension = LambdaExtension::new(listener.local_addr().unwrap(), "a-token");
+
+        let actual = futures::future::join_all((0..10).map(|_| extension.is_running())).await;
+        let probes = std::iter::from_fn(|| listener.accept().ok()).count();
+
+        assert!(actual.into_iter().all(|running| running));
+        assert_eq!(probes, 1);
+    }
+}
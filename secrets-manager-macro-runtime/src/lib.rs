@@ -0,0 +1,89 @@
+#![doc = include_str!("../README.md")]
+
+mod cache;
+mod errors;
+#[cfg(test)]
+mod fixtures;
+mod instrumentation;
+mod local_files;
+mod metadata;
+mod mounted_dir;
+mod observer;
+mod parameters;
+mod rotation;
+mod secret_string;
+mod secrets;
+mod source;
+#[cfg(feature = "rt-tokio")]
+mod backend;
+#[cfg(feature = "rt-tokio")]
+mod blocking;
+#[cfg(feature = "rt-tokio")]
+mod config;
+#[cfg(feature = "rt-tokio")]
+mod http;
+#[cfg(feature = "rt-tokio")]
+mod lambda_extension;
+#[cfg(feature = "rt-tokio")]
+mod runtime;
+#[cfg(feature = "rt-tokio")]
+mod vault;
+#[cfg(feature = "rt-tokio")]
+mod watch;
+
+pub use secrets_manager_macro::{build_parameters_struct, build_secrets_struct};
+
+pub use crate::cache::SecretCache;
+pub use crate::errors::{Error, ErrorKind};
+#[doc(hidden)]
+pub use crate::instrumentation::{instrumented, FetchContext};
+pub use crate::local_files::LocalFiles;
+#[doc(hidden)]
+pub use crate::metadata::Metadata;
+pub use crate::mounted_dir::MountedDir;
+pub use crate::observer::{set_observer, FetchEvent, FetchObserver};
+#[doc(hidden)]
+pub use crate::parameters::{get_parameters, split_string_list};
+pub use crate::rotation::CurrentAndPrevious;
+pub use crate::secret_string::SecretString;
+pub use crate::secrets::{Secret, Version};
+#[doc(hidden)]
+pub use crate::secrets::{find_env, get_secret};
+pub use crate::source::SecretSource;
+#[doc(hidden)]
+pub use crate::source::Origin;
+#[cfg(feature = "rt-tokio")]
+pub use crate::backend::Backend;
+#[cfg(feature = "rt-tokio")]
+#[doc(hidden)]
+pub use crate::backend::SecretsClient;
+#[cfg(feature = "rt-tokio")]
+#[doc(hidden)]
+pub use crate::blocking::block_on;
+#[cfg(feature = "rt-tokio")]
+#[doc(hidden)]
+pub use crate::config::load_config;
+#[cfg(feature = "rt-tokio")]
+pub use crate::lambda_extension::LambdaExtension;
+#[cfg(feature = "rt-tokio")]
+#[doc(hidden)]
+pub use crate::runtime::on_runtime;
+#[cfg(feature = "rt-tokio")]
+pub use crate::vault::Vault;
+#[cfg(feature = "rt-tokio")]
+#[doc(hidden)]
+pub use crate::watch::watch;
+#[cfg(feature = "rt-tokio")]
+pub use crate::watch::Handle;
+
+// the generated code uses these through this crate, so you do not need to add them (in the right version) yourself
+pub use async_trait::async_trait;
+pub use aws_config;
+pub use aws_sdk_secretsmanager;
+pub use aws_sdk_ssm;
+pub use futures;
+pub use serde_json;
+#[cfg(feature = "rt-tokio")]
+pub use tokio;
+#[cfg(feature = "tracing")]
+pub use tracing;
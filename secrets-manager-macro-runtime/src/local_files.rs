@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+
+use crate::errors::Error;
+use crate::source::SecretSource;
+
+const DIR_VARIABLE: &str = "SECRETS_DIR";
+const DEFAULT_DIR: &str = ".secrets";
+
+/// Reads secrets from JSON files in a local directory, so you can develop without AWS access.
+/// A secret named `/dev/some-secret` is read from `<dir>/dev/some-secret.json`.
+/// Files only have a current version
+pub struct LocalFiles {
+    dir: PathBuf,
+}
+
+impl LocalFiles {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        LocalFiles {
+            dir: dir.into(),
+        }
+    }
+
+    /// The directory in `SECRETS_DIR`, or `.secrets`
+    pub fn from_env() -> Self {
+        LocalFiles::new(std::env::var(DIR_VARIABLE).unwrap_or_else(|_| DEFAULT_DIR.to_string()))
+    }
+}
+
+#[async_trait]
+impl SecretSource for LocalFiles {
+    async fn fetch(&self, secret_name: &str) -> Result<HashMap<String, String>, Error> {
+        let path = self.dir.join(format!("{}.json", secret_name.trim_start_matches('/')));
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| Error::NotFound(format!("could not read file {} for secret with name {}: {}", path.display(), secret_name, e)))?;
+        Ok(serde_json::from_str(&content)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::TempDir;
+    use crate::secrets::Version;
+
+    use super::*;
+
+    fn create_dir() -> TempDir {
+        let dir = TempDir::new();
+        dir.write("dev/some-secret.json", r#"{ "key1": "value1" }"#);
+        dir
+    }
+
+    #[tokio::test]
+    async fn fetch_should_read_file_for_secret_name() {
+        let dir = create_dir();
+        let local_files = LocalFiles::new(dir.path());
+
+        let actual = local_files.fetch("/dev/some-secret").await.unwrap();
+
+        assert_eq!(actual.get("key1").unwrap(), "value1");
+    }
+
+    #[tokio::test]
+    async fn fetch_should_return_not_found_for_missing_file() {
+        let dir = create_dir();
+        let local_files = LocalFiles::new(dir.path());
+
+        let actual = local_files.fetch("/prod/some-secret").await;
+
+        assert!(matches!(actual, Err(Error::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn fetch_version_should_return_not_found_for_previous_version() {
+        let dir = create_dir();
+        let local_files = LocalFiles::new(dir.path());
+
+        let actual = local_files.fetch_version("/dev/some-secret", &Version::Stage("AWSPREVIOUS".to_string())).await;
+
+        assert!(matches!(actual, Err(Error::NotFound(_))));
+    }
+}
@@ -0,0 +1,96 @@
+use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::time::SystemTime;
+
+use crate::source::Origin;
+
+/// When, which version and from where a generated struct retrieved its values.
+/// Ignored when comparing, hashing or debug printing, so derives on the struct only look at the values
+#[derive(Clone)]
+pub struct Metadata {
+    fetched_at: SystemTime,
+    version_id: Option<String>,
+    origin: Option<Origin>,
+}
+
+impl Metadata {
+    /// Values retrieved just now. Composed structs have no origin of their own, their groups do
+    pub fn new(version_id: Option<String>, origin: Option<Origin>) -> Self {
+        Metadata {
+            fetched_at: SystemTime::now(),
+            version_id,
+            origin,
+        }
+    }
+
+    pub fn fetched_at(&self) -> SystemTime {
+        self.fetched_at
+    }
+
+    pub fn version_id(&self) -> Option<&str> {
+        self.version_id.as_deref()
+    }
+
+    pub fn origin(&self) -> Option<&Origin> {
+        self.origin.as_ref()
+    }
+}
+
+// for a derived `Default`, the values were never retrieved
+impl Default for Metadata {
+    fn default() -> Self {
+        Metadata {
+            fetched_at: SystemTime::UNIX_EPOCH,
+            version_id: None,
+            origin: None,
+        }
+    }
+}
+
+impl PartialEq for Metadata {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for Metadata {}
+
+impl Hash for Metadata {
+    fn hash<H: Hasher>(&self, _state: &mut H) {}
+}
+
+impl Debug for Metadata {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("..")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::hash_map::DefaultHasher;
+
+    use super::*;
+
+    fn hash(metadata: &Metadata) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        metadata.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn metadata_should_be_ignored_when_comparing_and_hashing() {
+        let first = Metadata::new(Some("v1".to_string()), None);
+        let second = Metadata::default();
+
+        assert_eq!(first, second);
+        assert_eq!(hash(&first), hash(&second));
+    }
+
+    #[test]
+    fn default_should_never_have_been_fetched() {
+        let actual = Metadata::default();
+
+        assert_eq!(actual.fetched_at(), SystemTime::UNIX_EPOCH);
+        assert!(actual.origin().is_none());
+    }
+}
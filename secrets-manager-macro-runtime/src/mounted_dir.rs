@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+
+use crate::errors::Error;
+use crate::source::SecretSource;
+
+const DIR_VARIABLE: &str = "SECRETS_MOUNT_DIR";
+const DEFAULT_DIR: &str = "/mnt/secrets";
+
+/// Reads secrets that are mounted as one file per key, like the Secrets Store CSI driver does in Kubernetes.
+/// The key `firstKey` of a secret named `/dev/some-secret` is read from `<dir>/dev/some-secret/firstKey`.
+/// Mounted secrets only have a current version
+pub struct MountedDir {
+    dir: PathBuf,
+}
+
+impl MountedDir {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        MountedDir {
+            dir: dir.into(),
+        }
+    }
+
+    /// The directory in `SECRETS_MOUNT_DIR`, or `/mnt/secrets`
+    pub fn from_env() -> Self {
+        MountedDir::new(std::env::var(DIR_VARIABLE).unwrap_or_else(|_| DEFAULT_DIR.to_string()))
+    }
+}
+
+#[async_trait]
+impl SecretSource for MountedDir {
+    async fn fetch(&self, secret_name: &str) -> Result<HashMap<String, String>, Error> {
+        let secret_dir = self.dir.join(secret_name.trim_start_matches('/'));
+        let entries = std::fs::read_dir(&secret_dir)
+            .map_err(|e| Error::NotFound(format!("could not read directory {} for secret with name {}: {}", secret_dir.display(), secret_name, e)))?;
+
+        let mut values = HashMap::new();
+        for entry in entries.flatten() {
+            let key = entry.file_name().to_string_lossy().to_string();
+            // Kubernetes adds hidden entries like ..data next to the keys
+            if key.starts_with('.') || !entry.path().is_file() {
+                continue;
+            }
+            let value = std::fs::read_to_string(entry.path())
+                .map_err(|e| Error::NotFound(format!("could not read key {} of secret with name {}: {}", key, secret_name, e)))?;
+            values.insert(key, value);
+        }
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::TempDir;
+
+    use super::*;
+
+    fn create_dir() -> TempDir {
+        let dir = TempDir::new();
+        dir.write("dev/some-secret/firstKey", "firstValue")
+            .write("dev/some-secret/secondKey", "secondValue")
+            .write("dev/some-secret/.hidden", "hidden")
+            .write("dev/some-secret/..2023_01_01/firstKey", "firstValue");
+        dir
+    }
+
+    #[tokio::test]
+    async fn fetch_should_read_a_file_per_key() {
+        let dir = create_dir();
+        let mounted_dir = MountedDir::new(dir.path());
+
+        let actual = mounted_dir.fetch("/dev/some-secret").await.unwrap();
+
+        assert_eq!(actual.get("firstKey").unwrap(), "firstValue");
+        assert_eq!(actual.get("secondKey").unwrap(), "secondValue");
+        assert_eq!(actual.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn fetch_should_return_not_found_for_missing_directory() {
+        let dir = create_dir();
+        let mounted_dir = MountedDir::new(dir.path());
+
+        let actual = mounted_dir.fetch("/prod/some-secret").await;
+
+        assert!(matches!(actual, Err(Error::NotFound(_))));
+    }
+}
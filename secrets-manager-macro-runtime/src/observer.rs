@@ -0,0 +1,93 @@
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use crate::errors::ErrorKind;
+
+/// A retrieval of a secret by a generated struct
+#[derive(Debug)]
+pub struct FetchEvent<'a> {
+    pub struct_name: &'a str,
+    pub secret_name: &'a str,
+    pub duration: Duration,
+    /// None when the retrieval succeeded
+    pub error: Option<ErrorKind>,
+}
+
+/// Notified of every retrieval of a secret, for example to push metrics
+pub trait FetchObserver: Send + Sync {
+    fn on_fetch(&self, event: &FetchEvent<'_>);
+}
+
+impl<F> FetchObserver for F where F: Fn(&FetchEvent<'_>) + Send + Sync {
+    fn on_fetch(&self, event: &FetchEvent<'_>) {
+        self(event)
+    }
+}
+
+static OBSERVER: RwLock<Option<Arc<dyn FetchObserver>>> = RwLock::new(None);
+
+/// Registers the observer for all generated structs, replacing any previous one
+pub fn set_observer(observer: impl FetchObserver + 'static) {
+    let mut current = OBSERVER.write().unwrap_or_else(|e| e.into_inner());
+    *current = Some(Arc::new(observer));
+}
+
+pub(crate) fn notify(event: &FetchEvent<'_>) {
+    // cloned out of the lock, so a slow observer does not block others, and it can replace itself without a deadlock
+    let current = OBSERVER.read().unwrap_or_else(|e| e.into_inner()).clone();
+    if let Some(observer) = current {
+        observer.on_fetch(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    // the observer is global, so tests that register one take turns
+    static REGISTERING: Mutex<()> = Mutex::new(());
+
+    fn event(struct_name: &'static str) -> FetchEvent<'static> {
+        FetchEvent {
+            struct_name,
+            secret_name: "observed-secret",
+            duration: Duration::from_millis(10),
+            error: Some(ErrorKind::NotFound),
+        }
+    }
+
+    #[test]
+    fn notify_should_pass_event_to_registered_observer() {
+        let _registering = REGISTERING.lock().unwrap_or_else(|e| e.into_inner());
+        let seen = Arc::new(Mutex::new(vec![]));
+        let seen_by_observer = seen.clone();
+        set_observer(move |event: &FetchEvent<'_>| {
+            if event.struct_name == "ObservedSecret" {
+                seen_by_observer.lock().unwrap().push((event.secret_name.to_string(), event.error));
+            }
+        });
+
+        notify(&event("ObservedSecret"));
+
+        assert_eq!(*seen.lock().unwrap(), vec![("observed-secret".to_string(), Some(ErrorKind::NotFound))]);
+    }
+
+    #[test]
+    fn notify_should_let_observer_replace_itself() {
+        let _registering = REGISTERING.lock().unwrap_or_else(|e| e.into_inner());
+        let replaced = Arc::new(Mutex::new(false));
+        let replaced_by_observer = replaced.clone();
+        set_observer(move |event: &FetchEvent<'_>| {
+            if event.struct_name == "ReplacingSecret" {
+                set_observer(|_: &FetchEvent<'_>| {});
+                *replaced_by_observer.lock().unwrap() = true;
+            }
+        });
+
+        notify(&event("ReplacingSecret"));
+
+        assert!(*replaced.lock().unwrap());
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use aws_sdk_ssm::output::GetParametersByPathOutput;
+use aws_sdk_ssm::types::SdkError;
+use aws_sdk_ssm::Client;
+
+use crate::errors::Error;
+use crate::secrets::Secret;
+use crate::source::SecretSource;
+
+/// Retrieves all parameters under the given path, decrypting secure strings.
+/// Their names, relative to the path, are the keys of the returned values
+pub async fn get_parameters(client: &Client, path: &str) -> Result<Secret, Error> {
+    let mut values = HashMap::new();
+    let mut next_token = None;
+
+    loop {
+        let output = client
+            .get_parameters_by_path()
+            .path(path)
+            .recursive(true)
+            .with_decryption(true)
+            .set_next_token(next_token)
+            .send()
+            .await
+            .map_err(|e| match e {
+                SdkError::ServiceError(v) => Error::Aws(format!(
+                    "could not get parameters under path {} ({})",
+                    path,
+                    v.err().message().unwrap_or_default()
+                )),
+                _ => Error::Aws(format!("could not get parameters under path {}: {}", path, e)),
+            })?;
+        add_parameters(&mut values, &output, path);
+
+        next_token = output.next_token().map(|t| t.to_string());
+        if next_token.is_none() {
+            break;
+        }
+    }
+
+    if values.is_empty() {
+        return Err(Error::NotFound(format!("could not find any parameters under path {}", path)));
+    }
+    Ok(Secret::new(values, None))
+}
+
+/// Parameter Store as the source of a generated parameters struct. The secret name is the path,
+/// so parameter structs can be refreshed like secrets
+pub(crate) struct ParameterStore(pub(crate) Client);
+
+#[async_trait]
+impl SecretSource for ParameterStore {
+    async fn fetch(&self, path: &str) -> Result<HashMap<String, String>, Error> {
+        Ok(get_parameters(&self.0, path).await?.into_values())
+    }
+}
+
+/// The values of a `StringList` parameter, which are separated by commas
+pub fn split_string_list(value: &str) -> Vec<String> {
+    value.split(',').map(|v| v.to_string()).collect()
+}
+
+fn add_parameters(values: &mut HashMap<String, String>, output: &GetParametersByPathOutput, path: &str) {
+    for parameter in output.parameters().unwrap_or_default() {
+        if let (Some(name), Some(value)) = (parameter.name(), parameter.value()) {
+            let relative_name = name.strip_prefix(path).unwrap_or(name).trim_start_matches('/');
+            values.insert(relative_name.to_string(), value.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_sdk_ssm::model::Parameter;
+
+    use super::*;
+
+    #[test]
+    fn add_parameters_should_add_values_by_name_relative_to_path() {
+        let output = GetParametersByPathOutput::builder()
+            .parameters(Parameter::builder().name("/app/dev/firstParameter").value("firstValue").build())
+            .parameters(Parameter::builder().name("/app/dev/nested/secondParameter").value("secondValue").build())
+            .build();
+        let mut values = HashMap::new();
+
+        add_parameters(&mut values, &output, "/app/dev");
+
+        assert_eq!(values.get("firstParameter").unwrap(), "firstValue");
+        assert_eq!(values.get("nested/secondParameter").unwrap(), "secondValue");
+    }
+
+    #[test]
+    fn split_string_list_should_return_each_value() {
+        let actual = split_string_list("first,second,third");
+
+        assert_eq!(actual, vec!["first", "second", "third"]);
+    }
+
+    #[test]
+    fn add_parameters_should_skip_parameters_without_value() {
+        let output = GetParametersByPathOutput::builder()
+            .parameters(Parameter::builder().name("/app/dev/firstParameter").build())
+            .build();
+        let mut values = HashMap::new();
+
+        add_parameters(&mut values, &output, "/app/dev");
+
+        assert!(values.is_empty());
+    }
+}
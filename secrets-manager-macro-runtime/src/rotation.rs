@@ -0,0 +1,78 @@
+use std::future::Future;
+
+/// The current and the previous version of a secret, for use during a rotation
+pub struct CurrentAndPrevious<T> {
+    pub current: T,
+    /// Absent when the secret was never rotated
+    pub previous: Option<T>,
+}
+
+impl<T> CurrentAndPrevious<T> {
+    /// Runs `action` with the current version. When this fails with an error for which `is_auth_error` returns true,
+    /// `action` is retried with the previous version (if there is one)
+    pub async fn with_fallback<F, Fut, R, E, A>(&self, action: F, is_auth_error: A) -> Result<R, E>
+        where F: Fn(&T) -> Fut,
+              Fut: Future<Output=Result<R, E>>,
+              A: Fn(&E) -> bool {
+        match action(&self.current).await {
+            Err(e) if is_auth_error(&e) => match &self.previous {
+                Some(previous) => action(previous).await,
+                None => Err(e),
+            },
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum FakeError {
+        Auth,
+        Other,
+    }
+
+    async fn login(password: String) -> Result<String, FakeError> {
+        match password.as_str() {
+            "previous" => Ok("logged in with previous".to_string()),
+            "broken" => Err(FakeError::Other),
+            _ => Err(FakeError::Auth),
+        }
+    }
+
+    fn credentials(current: &str, previous: Option<&str>) -> CurrentAndPrevious<String> {
+        CurrentAndPrevious {
+            current: current.to_string(),
+            previous: previous.map(|p| p.to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn with_fallback_should_retry_with_previous_on_auth_error() {
+        let credentials = credentials("current", Some("previous"));
+
+        let actual = credentials.with_fallback(|c| login(c.clone()), |e| *e == FakeError::Auth).await;
+
+        assert_eq!(actual, Ok("logged in with previous".to_string()));
+    }
+
+    #[tokio::test]
+    async fn with_fallback_should_not_retry_on_other_errors() {
+        let credentials = credentials("broken", Some("previous"));
+
+        let actual = credentials.with_fallback(|c| login(c.clone()), |e| *e == FakeError::Auth).await;
+
+        assert_eq!(actual, Err(FakeError::Other));
+    }
+
+    #[tokio::test]
+    async fn with_fallback_should_return_auth_error_when_there_is_no_previous() {
+        let credentials = credentials("current", None);
+
+        let actual = credentials.with_fallback(|c| login(c.clone()), |e| *e == FakeError::Auth).await;
+
+        assert_eq!(actual, Err(FakeError::Auth));
+    }
+}
@@ -0,0 +1,102 @@
+use std::future::Future;
+use std::sync::Mutex;
+
+use tokio::runtime::{Builder, Handle, Runtime};
+
+use crate::errors::Error;
+
+// built on first use from outside of a tokio runtime, and kept for the rest of the process like the clients that run on it
+static SHARED: Mutex<Option<Runtime>> = Mutex::new(None);
+
+/// Runs the future on the current tokio runtime, or on a shared one when called from another executor
+/// (async-std, smol, `futures::executor`...), so the SDK, the Lambda extension and Vault always have a tokio reactor
+pub async fn on_runtime<F, T>(future: F) -> Result<T, Error>
+    where F: Future<Output=Result<T, Error>> + Send + 'static,
+          T: Send + 'static {
+    if Handle::try_current().is_ok() {
+        return future.await;
+    }
+
+    match handle()?.spawn(future).await {
+        Ok(result) => result,
+        Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+        Err(e) => Err(Error::Runtime(e.to_string())),
+    }
+}
+
+/// The current tokio runtime, or the shared one when there is none
+pub(crate) fn handle() -> Result<Handle, Error> {
+    if let Ok(current) = Handle::try_current() {
+        return Ok(current);
+    }
+
+    let mut shared = SHARED.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(runtime) = shared.as_ref() {
+        return Ok(runtime.handle().clone());
+    }
+    let runtime = Builder::new_multi_thread()
+        .worker_threads(1)
+        .thread_name("secrets-manager-macro")
+        .enable_all()
+        .build()
+        .map_err(|e| Error::Runtime(e.to_string()))?;
+    let handle = runtime.handle().clone();
+    *shared = Some(runtime);
+    Ok(handle)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake};
+    use std::thread::Thread;
+
+    use super::*;
+
+    struct Unpark(Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    // an executor without a tokio reactor, like async-std or smol
+    fn block_on_other_executor<F: Future>(future: F) -> F::Output {
+        let waker = Arc::new(Unpark(std::thread::current())).into();
+        let mut context = Context::from_waker(&waker);
+        let mut future: Pin<Box<F>> = Box::pin(future);
+
+        loop {
+            match future.as_mut().poll(&mut context) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => std::thread::park(),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn on_runtime_should_run_future_inside_of_runtime() {
+        let actual = on_runtime(async { Ok(1) }).await;
+
+        assert_eq!(actual.unwrap(), 1);
+    }
+
+    #[test]
+    fn on_runtime_should_provide_reactor_outside_of_runtime() {
+        let actual = block_on_other_executor(on_runtime(async {
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+            Ok(Handle::try_current().is_ok())
+        }));
+
+        assert!(actual.unwrap());
+    }
+
+    #[test]
+    fn on_runtime_should_return_error_of_future() {
+        let actual: Result<(), Error> = block_on_other_executor(on_runtime(async { Err(Error::MissingKey("key".to_string())) }));
+
+        assert!(matches!(actual, Err(Error::MissingKey(_))));
+    }
+}
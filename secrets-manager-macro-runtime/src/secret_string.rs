@@ -0,0 +1,50 @@
+use std::fmt::{Debug, Formatter};
+
+// there are libraries for secret strings, but this way no additional import is needed (there are enough already)
+/// String that only shows its first character when debug printed
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct SecretString(String);
+
+impl SecretString {
+    pub fn new(input: String) -> Self {
+        SecretString(input)
+    }
+}
+
+impl Debug for SecretString {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&format!("{}****", self.0.chars().next().unwrap_or('*')))
+    }
+}
+
+impl AsRef<str> for SecretString {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn debug_should_only_show_first_character() {
+        let secret = SecretString::new("password".to_string());
+
+        assert_eq!(format!("{:?}", secret), "p****");
+    }
+
+    #[test]
+    fn debug_should_hide_empty_value() {
+        let secret = SecretString::new("".to_string());
+
+        assert_eq!(format!("{:?}", secret), "*****");
+    }
+
+    #[test]
+    fn as_ref_should_return_value() {
+        let secret = SecretString::new("password".to_string());
+
+        assert_eq!(secret.as_ref(), "password");
+    }
+}
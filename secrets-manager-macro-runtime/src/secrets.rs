@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use aws_sdk_secretsmanager::output::GetSecretValueOutput;
+use aws_sdk_secretsmanager::types::SdkError;
+use aws_sdk_secretsmanager::Client;
+
+use crate::errors::Error;
+use crate::source::SecretSource;
+
+/// The values of a secret, and the version they belong to
+#[derive(Clone)]
+pub struct Secret {
+    values: HashMap<String, String>,
+    version_id: Option<String>,
+}
+
+impl Secret {
+    pub fn new(values: HashMap<String, String>, version_id: Option<String>) -> Self {
+        Secret {
+            values,
+            version_id,
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Result<String, Error> {
+        self.values.get(key)
+            .cloned()
+            .ok_or_else(|| Error::MissingKey(key.to_string()))
+    }
+
+    pub fn version_id(&self) -> Option<&str> {
+        self.version_id.as_deref()
+    }
+
+    pub fn into_values(self) -> HashMap<String, String> {
+        self.values
+    }
+
+    /// Sets every key of `keys` for which an environment variable `{prefix}_{KEY}` is present, even when the secret lacks it.
+    /// Characters that cannot appear in a variable name, like `-` and `.`, become `_`, as they do in parameter fields.
+    /// Each override is logged with the key and variable name (through `tracing` with that feature, on stderr otherwise), but never with its value
+    pub fn with_env_overrides(mut self, prefix: &str, keys: &[&str]) -> Self {
+        for key in keys {
+            let variable = env_override_variable(prefix, key);
+            if let Ok(override_value) = std::env::var(&variable) {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(key = %key, variable = %variable, "overriding secret key with environment variable");
+                #[cfg(not(feature = "tracing"))]
+                eprintln!("overriding secret key {} with environment variable {}", key, variable);
+                self.values.insert(key.to_string(), override_value);
+            }
+        }
+        self
+    }
+}
+
+fn env_override_variable(prefix: &str, key: &str) -> String {
+    let key: String = key.chars()
+        .map(|c| if c.is_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+        .collect();
+    format!("{}_{}", prefix, key)
+}
+
+impl TryFrom<GetSecretValueOutput> for Secret {
+    type Error = Error;
+
+    fn try_from(output: GetSecretValueOutput) -> Result<Self, Self::Error> {
+        let content = output
+            .secret_string()
+            .map_or_else(|| "{}".to_string(), |v| v.to_string());
+        let values = serde_json::from_str(&content)?;
+        Ok(Secret::new(values, output.version_id().map(|v| v.to_string())))
+    }
+}
+
+/// Which version of a secret to retrieve
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Version {
+    /// The version with stage `AWSCURRENT`
+    Current,
+    Stage(String),
+    Id(String),
+}
+
+impl Version {
+    /// Whether this is the current version, which every source has
+    pub fn is_current(&self) -> bool {
+        match self {
+            Version::Current => true,
+            Version::Stage(stage) => stage == "AWSCURRENT",
+            Version::Id(_) => false,
+        }
+    }
+}
+
+pub async fn get_secret(client: &Client, secret_name: &str, version: &Version) -> Result<Secret, Error> {
+    let request = client
+        .get_secret_value()
+        .secret_id(secret_name);
+    let request = match version {
+        Version::Current => request,
+        Version::Stage(stage) => request.version_stage(stage),
+        Version::Id(id) => request.version_id(id),
+    };
+
+    let output = request
+        .send()
+        .await
+        .map_err(|e| match e {
+            SdkError::ServiceError(v) if v.err().is_resource_not_found_exception() => Error::NotFound(format!(
+                "could not find secret with name {} and version {:?}",
+                secret_name,
+                version
+            )),
+            SdkError::ServiceError(v) => Error::Aws(format!(
+                "could not get secret with name {} ({})",
+                secret_name,
+                v.err().message().unwrap_or_default()
+            )),
+            _ => Error::Aws(format!("could not get secret with name {}: {}", secret_name, e)),
+        })?;
+    Secret::try_from(output)
+}
+
+#[async_trait]
+impl SecretSource for Client {
+    async fn fetch(&self, secret_name: &str) -> Result<HashMap<String, String>, Error> {
+        Ok(get_secret(self, secret_name, &Version::Current).await?.into_values())
+    }
+
+    async fn fetch_version(&self, secret_name: &str, version: &Version) -> Result<Secret, Error> {
+        get_secret(self, secret_name, version).await
+    }
+}
+
+/// Value of the first of the given environment variables that is present
+pub fn find_env(variables: &[&str]) -> Option<String> {
+    variables.iter().flat_map(std::env::var).next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secret_should_be_created_from_output_with_values_and_version() {
+        let output = GetSecretValueOutput::builder()
+            .secret_string(r#"{ "key1": "value1", "key2": "value2" }"#)
+            .version_id("a-version")
+            .build();
+
+        let actual = Secret::try_from(output).unwrap();
+
+        assert_eq!(actual.get("key1").unwrap(), "value1");
+        assert_eq!(actual.get("key2").unwrap(), "value2");
+        assert_eq!(actual.version_id(), Some("a-version"));
+    }
+
+    #[test]
+    fn with_env_overrides_should_only_replace_keys_with_variable() {
+        std::env::set_var("OVERRIDETEST_KEY1", "overridden");
+        let values = HashMap::from([
+            ("key1".to_string(), "value1".to_string()),
+            ("key2".to_string(), "value2".to_string()),
+        ]);
+
+        let actual = Secret::new(values, None).with_env_overrides("OVERRIDETEST", &["key1", "key2"]);
+
+        assert_eq!(actual.get("key1").unwrap(), "overridden");
+        assert_eq!(actual.get("key2").unwrap(), "value2");
+    }
+
+    #[test]
+    fn with_env_overrides_should_supply_missing_keys() {
+        std::env::set_var("MISSINGOVERRIDETEST_KEY1", "supplied");
+
+        let actual = Secret::new(HashMap::new(), None).with_env_overrides("MISSINGOVERRIDETEST", &["key1", "key2"]);
+
+        assert_eq!(actual.get("key1").unwrap(), "supplied");
+        assert!(actual.get("key2").is_err());
+    }
+
+    #[test]
+    fn with_env_overrides_should_replace_dashes_and_dots_in_variable_name() {
+        std::env::set_var("DASHOVERRIDETEST_SOME_NESTED_KEY", "overridden");
+        let values = HashMap::from([("some-nested.key".to_string(), "value".to_string())]);
+
+        let actual = Secret::new(values, None).with_env_overrides("DASHOVERRIDETEST", &["some-nested.key"]);
+
+        assert_eq!(actual.get("some-nested.key").unwrap(), "overridden");
+    }
+
+    #[test]
+    fn secret_should_be_empty_when_output_has_no_secret_string() {
+        let output = GetSecretValueOutput::builder().build();
+
+        let actual = Secret::try_from(output).unwrap();
+
+        assert!(actual.values.is_empty());
+        assert_eq!(actual.version_id(), None);
+    }
+
+    #[test]
+    fn secret_should_fail_for_invalid_json() {
+        let output = GetSecretValueOutput::builder()
+            .secret_string("{ invalid }")
+            .build();
+
+        let actual = Secret::try_from(output);
+
+        assert!(matches!(actual, Err(Error::Json(_))));
+    }
+
+    #[test]
+    fn get_should_return_error_for_missing_key() {
+        let secret = Secret::new(HashMap::new(), None);
+
+        let actual = secret.get("unknown");
+
+        assert!(matches!(actual, Err(Error::MissingKey(key)) if key == "unknown"));
+    }
+
+    #[test]
+    fn find_env_should_return_first_present_variable() {
+        std::env::set_var("SECRETS_RUNTIME_TEST_SECOND_ENV", "prod");
+
+        let actual = find_env(&["SECRETS_RUNTIME_TEST_FIRST_ENV", "SECRETS_RUNTIME_TEST_SECOND_ENV"]);
+
+        assert_eq!(actual, Some("prod".to_string()));
+    }
+
+    #[test]
+    fn find_env_should_return_none_when_no_variable_is_present() {
+        let actual = find_env(&["SECRETS_RUNTIME_TEST_UNKNOWN_ENV"]);
+
+        assert_eq!(actual, None);
+    }
+}
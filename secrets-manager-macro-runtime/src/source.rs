@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+#[cfg(feature = "rt-tokio")]
+use crate::backend::SecretsClient;
+use crate::errors::Error;
+use crate::parameters::ParameterStore;
+use crate::secrets::{Secret, Version};
+
+/// Somewhere secrets can be retrieved from. Implement it to plug in your own backend (an encrypted cache,
+/// a fake for tests, an in-house secret service...) and pass it to the generated `new_from_source`
+#[async_trait]
+pub trait SecretSource: Send + Sync {
+    /// The current values of the secret with the given name
+    async fn fetch(&self, secret_name: &str) -> Result<HashMap<String, String>, Error>;
+
+    /// A specific version of the secret. By default, only the current version (`AWSCURRENT`) is found
+    async fn fetch_version(&self, secret_name: &str, version: &Version) -> Result<Secret, Error> {
+        if version.is_current() {
+            Ok(Secret::new(self.fetch(secret_name).await?, None))
+        } else {
+            Err(Error::NotFound(format!(
+                "could not find secret with name {} and version {:?}, this source only has a current version",
+                secret_name,
+                version
+            )))
+        }
+    }
+}
+
+// so a source can be shared, for example by all secrets of a composed struct
+#[async_trait]
+impl<S: SecretSource + ?Sized> SecretSource for Arc<S> {
+    async fn fetch(&self, secret_name: &str) -> Result<HashMap<String, String>, Error> {
+        (**self).fetch(secret_name).await
+    }
+
+    async fn fetch_version(&self, secret_name: &str, version: &Version) -> Result<Secret, Error> {
+        (**self).fetch_version(secret_name, version).await
+    }
+}
+
+/// The source and version a generated struct was retrieved with, so refreshing retrieves the same version from the same source
+#[derive(Clone)]
+pub struct Origin {
+    pub source: Arc<dyn SecretSource>,
+    pub version: Version,
+    /// The configured backend, which needs a tokio reactor and caches per backend.
+    /// `None` for a source or config passed by the caller, which bypass the cache and run on the executor of the caller
+    pub backend: Option<&'static str>,
+}
+
+impl Origin {
+    pub fn new(source: Arc<dyn SecretSource>, version: Version) -> Self {
+        Origin {
+            source,
+            version,
+            backend: None,
+        }
+    }
+
+    #[cfg(feature = "rt-tokio")]
+    pub fn configured(client: SecretsClient, version: Version) -> Self {
+        Origin {
+            backend: Some(client.name()),
+            source: Arc::new(client),
+            version,
+        }
+    }
+
+    /// The same source at another version
+    pub fn at(&self, version: Version) -> Self {
+        Origin {
+            version,
+            ..self.clone()
+        }
+    }
+
+    /// Parameter Store with the config of the caller
+    pub fn parameter_store(client: aws_sdk_ssm::Client) -> Self {
+        Origin::new(Arc::new(ParameterStore(client)), Version::Current)
+    }
+
+    /// Parameter Store with the config from the environment, the only backend for parameters
+    #[cfg(feature = "rt-tokio")]
+    pub fn configured_parameter_store(client: aws_sdk_ssm::Client) -> Self {
+        Origin {
+            backend: Some("parameter_store"),
+            ..Origin::parameter_store(client)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FakeSource;
+
+    #[async_trait]
+    impl SecretSource for FakeSource {
+        async fn fetch(&self, secret_name: &str) -> Result<HashMap<String, String>, Error> {
+            Ok(HashMap::from([("name".to_string(), secret_name.to_string())]))
+        }
+    }
+
+    #[tokio::test]
+    async fn fetch_version_should_fetch_current_version() {
+        let actual = FakeSource.fetch_version("some-secret", &Version::Stage("AWSCURRENT".to_string())).await.unwrap();
+
+        assert_eq!(actual.get("name").unwrap(), "some-secret");
+    }
+
+    #[tokio::test]
+    async fn fetch_version_should_not_find_other_versions() {
+        let actual = FakeSource.fetch_version("some-secret", &Version::Id("a-version".to_string())).await;
+
+        assert!(matches!(actual, Err(Error::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn fetch_should_work_for_shared_source() {
+        let source: Arc<dyn SecretSource> = Arc::new(FakeSource);
+
+        let actual = source.fetch("some-secret").await.unwrap();
+
+        assert_eq!(actual.get("name").unwrap(), "some-secret");
+    }
+}
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use hyper::StatusCode;
+
+use crate::errors::Error;
+use crate::http::{self, encode, encode_path};
+use crate::secrets::{Secret, Version};
+use crate::source::SecretSource;
+
+const TOKEN_HEADER: &str = "X-Vault-Token";
+// the mount of the KV v2 engine in a new Vault
+const DEFAULT_MOUNT: &str = "secret";
+
+/// Retrieves secrets from a HashiCorp Vault KV v2 engine.
+/// A secret named `/dev/some-secret` is read from mount `dev`, path `some-secret`.
+/// Secrets without an env are read from the `VAULT_MOUNT` (or `secret`) mount
+pub struct Vault {
+    address: String,
+    token: String,
+    default_mount: String,
+}
+
+impl Vault {
+    pub fn new(address: &str, token: &str, default_mount: &str) -> Self {
+        Vault {
+            address: address.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+            default_mount: default_mount.to_string(),
+        }
+    }
+
+    /// Vault at `VAULT_ADDR`, authenticated with `VAULT_TOKEN`, if both are present
+    pub fn from_env() -> Option<Self> {
+        let address = std::env::var("VAULT_ADDR").ok()?;
+        let token = std::env::var("VAULT_TOKEN").ok()?;
+        let default_mount = std::env::var("VAULT_MOUNT").unwrap_or_else(|_| DEFAULT_MOUNT.to_string());
+        Some(Vault::new(&address, &token, &default_mount))
+    }
+}
+
+#[async_trait]
+impl SecretSource for Vault {
+    async fn fetch(&self, secret_name: &str) -> Result<HashMap<String, String>, Error> {
+        Ok(self.fetch_version(secret_name, &Version::Current).await?.into_values())
+    }
+
+    async fn fetch_version(&self, secret_name: &str, version: &Version) -> Result<Secret, Error> {
+        let version_parameter = match version {
+            Version::Current => "".to_string(),
+            Version::Stage(stage) if stage == "AWSCURRENT" => "".to_string(),
+            Version::Id(id) => format!("?version={}", encode(id)),
+            Version::Stage(_) => return Err(Error::NotFound(format!(
+                "could not find secret with name {} and version {:?}, Vault only has version numbers",
+                secret_name,
+                version
+            ))),
+        };
+        let (mount, path) = self.mount_and_path(secret_name);
+        let uri = format!("{}/v1/{}/data/{}{}", self.address, encode_path(mount), encode_path(path), version_parameter);
+
+        let (status, body) = http::get(&uri, (TOKEN_HEADER, &self.token))
+            .await
+            .map_err(|e| Error::Vault(format!("could not reach Vault for secret with name {}: {}", secret_name, e)))?;
+
+        match status {
+            StatusCode::OK => secret_from_response(&body),
+            StatusCode::NOT_FOUND => Err(Error::NotFound(format!(
+                "could not find secret with name {} and version {:?}",
+                secret_name,
+                version
+            ))),
+            _ => Err(Error::Vault(format!(
+                "could not get secret with name {} from Vault (status {})",
+                secret_name,
+                status
+            ))),
+        }
+    }
+}
+
+impl Vault {
+    fn mount_and_path<'a>(&'a self, secret_name: &'a str) -> (&'a str, &'a str) {
+        match secret_name.strip_prefix('/').and_then(|name| name.split_once('/')) {
+            Some((mount, path)) => (mount, path),
+            None => (self.default_mount.as_str(), secret_name.trim_start_matches('/')),
+        }
+    }
+}
+
+// KV v2 wraps the values in a second `data`, next to the metadata with the version
+fn secret_from_response(body: &[u8]) -> Result<Secret, Error> {
+    let output: serde_json::Value = serde_json::from_slice(body)?;
+    let data = &output["data"];
+    let values: HashMap<String, String> = data["data"]
+        .as_object()
+        .map(|values| values.iter()
+            .map(|(key, value)| match value {
+                serde_json::Value::String(v) => (key.to_string(), v.to_string()),
+                other => (key.to_string(), other.to_string()),
+            })
+            .collect())
+        .unwrap_or_default();
+    let version_id = data["metadata"]["version"].as_u64().map(|v| v.to_string());
+    Ok(Secret::new(values, version_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::http::tests::stand_in;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn fetch_version_should_retrieve_secret_from_env_mount_with_token() {
+        let body = r#"{ "data": { "data": { "key1": "value1", "port": 5432 }, "metadata": { "version": 3 } } }"#;
+        let (address, handle) = stand_in("200 OK", body);
+        let vault = Vault::new(&format!("http://{}/", address), "a-token", "secret");
+
+        let actual = vault.fetch_version("/dev/some-secret", &Version::Current).await.unwrap();
+        let request = handle.join().unwrap().to_lowercase();
+
+        assert_eq!(actual.get("key1").unwrap(), "value1");
+        assert_eq!(actual.get("port").unwrap(), "5432");
+        assert_eq!(actual.version_id(), Some("3"));
+        assert!(request.starts_with("get /v1/dev/data/some-secret http/1.1"));
+        assert!(request.contains("x-vault-token: a-token"));
+    }
+
+    #[tokio::test]
+    async fn fetch_version_should_use_default_mount_and_version() {
+        let (address, handle) = stand_in("404 Not Found", r#"{ "errors": [] }"#);
+        let vault = Vault::new(&format!("http://{}", address), "a-token", "secret");
+
+        let actual = vault.fetch_version("NoPrefixSecret", &Version::Id("2".to_string())).await;
+        let request = handle.join().unwrap();
+
+        assert!(matches!(actual, Err(Error::NotFound(_))));
+        assert!(request.starts_with("GET /v1/secret/data/NoPrefixSecret?version=2 HTTP/1.1"));
+    }
+
+    #[tokio::test]
+    async fn fetch_version_should_encode_mount_and_path() {
+        let (address, handle) = stand_in("404 Not Found", r#"{ "errors": [] }"#);
+        let vault = Vault::new(&format!("http://{}", address), "a-token", "secret");
+
+        let _ = vault.fetch_version("/dev space/team/some secret#1", &Version::Current).await;
+        let request = handle.join().unwrap();
+
+        assert!(request.starts_with("GET /v1/dev%20space/data/team/some%20secret%231 HTTP/1.1"));
+    }
+
+    #[tokio::test]
+    async fn fetch_version_should_return_vault_error_when_unreachable() {
+        let vault = Vault::new("http://127.0.0.1:1", "a-token", "secret");
+
+        let actual = vault.fetch_version("/dev/some-secret", &Version::Current).await;
+
+        assert!(matches!(actual, Err(Error::Vault(_))));
+    }
+
+    #[tokio::test]
+    async fn fetch_version_should_not_find_aws_stages() {
+        let vault = Vault::new("http://127.0.0.1:1", "a-token", "secret");
+
+        let actual = vault.fetch_version("/dev/some-secret", &Version::Stage("AWSPREVIOUS".to_string())).await;
+
+        assert!(matches!(actual, Err(Error::NotFound(_))));
+    }
+}
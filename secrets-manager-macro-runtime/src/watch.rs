@@ -0,0 +1,144 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::watch::{self, Receiver};
+
+use crate::errors::Error;
+use crate::runtime;
+
+// a watch channel already swaps the value atomically, so no need for something like ArcSwap
+/// Cloneable handle to secret values that are refreshed in the background
+pub struct Handle<T> {
+    receiver: Receiver<Arc<T>>,
+}
+
+// derive would require T to be Clone
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        Handle {
+            receiver: self.receiver.clone(),
+        }
+    }
+}
+
+impl<T> Handle<T> {
+    /// Cheap snapshot of the latest values
+    pub fn current(&self) -> Arc<T> {
+        self.receiver.borrow().clone()
+    }
+
+    /// Receiver that is notified whenever a new version of the secret is retrieved
+    pub fn subscribe(&self) -> Receiver<Arc<T>> {
+        self.receiver.clone()
+    }
+}
+
+/// Retrieves the value again every `ttl` in a background task. When this fails, the last good value is kept
+/// (and a warning is logged with the `tracing` feature, next to the failed fetch that the observer already sees).
+/// Receivers are only notified when the version changes. Values without a version (like mounted files) are always replaced.
+/// Outside of a tokio runtime, the task runs on the shared runtime that also retrieves the secrets
+pub fn watch<T, F, Fut>(initial: T, ttl: Duration, retrieve: F, version_id: fn(&T) -> Option<&str>) -> Result<Handle<T>, Error>
+    where T: Send + Sync + 'static,
+          F: Fn() -> Fut + Send + 'static,
+          Fut: Future<Output=Result<T, Error>> + Send + 'static {
+    let (sender, receiver) = watch::channel(Arc::new(initial));
+
+    runtime::handle()?.spawn(async move {
+        loop {
+            tokio::time::sleep(ttl).await;
+
+            if sender.is_closed() {
+                break;
+            }
+            match retrieve().await {
+                Ok(refreshed) => {
+                    sender.send_if_modified(|current| {
+                        match (version_id(current), version_id(&refreshed)) {
+                            (Some(current_version), Some(refreshed_version)) if current_version == refreshed_version => false,
+                            _ => {
+                                *current = Arc::new(refreshed);
+                                true
+                            }
+                        }
+                    });
+                }
+                Err(error) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(%error, "could not refresh watched secret, keeping the last good value");
+                    #[cfg(not(feature = "tracing"))]
+                    let _ = error;
+                }
+            }
+        }
+    });
+
+    Ok(Handle {
+        receiver,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::fixtures::TempDir;
+    use crate::mounted_dir::MountedDir;
+    use crate::secrets::{Secret, Version};
+    use crate::source::SecretSource;
+
+    use super::*;
+
+    struct Versioned(String);
+
+    fn version_of(value: &Versioned) -> Option<&str> {
+        Some(&value.0)
+    }
+
+    #[tokio::test]
+    async fn watch_should_notify_receivers_of_new_version() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let calls_for_retrieve = calls.clone();
+
+        let handle = watch(Versioned("v1".to_string()), Duration::from_millis(10), move || {
+            let call = calls_for_retrieve.fetch_add(1, Ordering::SeqCst);
+            async move { Ok(Versioned(format!("v{}", call + 2))) }
+        }, version_of).unwrap();
+        let mut receiver = handle.subscribe();
+
+        receiver.changed().await.unwrap();
+
+        assert_eq!(handle.current().0, "v2");
+        assert_eq!(handle.clone().current().0, "v2");
+    }
+
+    #[tokio::test]
+    async fn watch_should_keep_last_good_value_when_retrieval_fails() {
+        let handle = watch(Versioned("v1".to_string()), Duration::from_millis(10), || async {
+            Err(Error::Aws("unavailable".to_string()))
+        }, version_of).unwrap();
+
+        tokio::time::sleep(Duration::from_millis(35)).await;
+
+        assert_eq!(handle.current().0, "v1");
+    }
+
+    #[tokio::test]
+    async fn watch_should_pick_up_rewritten_mounted_file() {
+        let dir = TempDir::new();
+        dir.write("dev/some-secret/firstKey", "firstValue");
+        let mounted_dir = Arc::new(MountedDir::new(dir.path()));
+        let initial = mounted_dir.fetch_version("/dev/some-secret", &Version::Current).await.unwrap();
+        dir.write("dev/some-secret/firstKey", "rotatedValue");
+
+        let handle = watch(initial, Duration::from_millis(10), move || {
+            let mounted_dir = mounted_dir.clone();
+            async move { mounted_dir.fetch_version("/dev/some-secret", &Version::Current).await }
+        }, Secret::version_id).unwrap();
+        let mut receiver = handle.subscribe();
+
+        receiver.changed().await.unwrap();
+
+        assert_eq!(handle.current().get("firstKey").unwrap(), "rotatedValue");
+    }
+}
@@ -5,7 +5,8 @@ use aws_sdk_secretsmanager::Client;
 use aws_sdk_secretsmanager::error::{GetSecretValueError, ListSecretsError};
 use aws_sdk_secretsmanager::types::SdkError;
 use crate::implementation::errors::RetrievalError;
-use crate::implementation::input::EnvSetting;
+use crate::implementation::input::{Env, EnvSetting};
+use crate::implementation::transformations::{self, NamePattern};
 use tokio_stream::StreamExt;
 
 pub struct SecretsManagerClient {
@@ -20,9 +21,9 @@ impl SecretsManagerClient {
         }
     }
 
-    pub async fn get_filtered_secret_list(&self, base_secret_names: Vec<String>, env_setting: &EnvSetting) -> Result<NonEmptySecrets, RetrievalError> {
+    pub async fn get_filtered_secret_list(&self, base_secret_names: Vec<String>, env_setting: &EnvSetting, pattern: Option<&NamePattern>) -> Result<NonEmptySecrets, RetrievalError> {
         let list_result = self.list_secrets().await?;
-        filter_secrets_list(list_result, base_secret_names, env_setting)
+        filter_secrets_list(list_result, base_secret_names, env_setting, pattern)
     }
 
     async fn list_secrets(&self) -> Result<Vec<ListSecretsOutput>, SdkError<ListSecretsError>> {
@@ -35,15 +36,24 @@ impl SecretsManagerClient {
             .await
     }
 
-    pub async fn get_secret_as_map(&self, full_secret_name: &str) -> Result<HashMap<String, String>, RetrievalError> {
-        let secret_value = self.get_secret(full_secret_name).await?;
+    pub async fn get_secret_as_map(&self, full_secret_name: &str, version_stage: Option<&str>) -> Result<HashMap<String, String>, RetrievalError> {
+        let secret_value = self.get_secret(full_secret_name, version_stage).await
+            .map_err(|e| match (e, version_stage) {
+                (SdkError::ServiceError(v), Some(stage)) if v.err().is_resource_not_found_exception() => RetrievalError::NotFound(format!(
+                    "Could not find version stage {} for secret {}",
+                    stage,
+                    full_secret_name
+                )),
+                (e, _) => e.into(),
+            })?;
         get_secret_value_as_map(secret_value)
     }
 
-    async fn get_secret(&self, secret_name: &str) -> Result<GetSecretValueOutput, SdkError<GetSecretValueError>> {
+    async fn get_secret(&self, secret_name: &str, version_stage: Option<&str>) -> Result<GetSecretValueOutput, SdkError<GetSecretValueError>> {
         self.client
             .get_secret_value()
             .secret_id(secret_name)
+            .set_version_stage(version_stage.map(|v| v.to_string()))
             .send()
             .await
     }
@@ -59,15 +69,18 @@ fn get_secret_value_as_map(output: GetSecretValueOutput) -> Result<HashMap<Strin
 // could be safer with private field
 pub struct NonEmptySecrets(pub Vec<String>);
 
-fn filter_secrets_list(output: Vec<ListSecretsOutput>, base_secret_names: Vec<String>, env_setting: &EnvSetting) -> Result<NonEmptySecrets, RetrievalError> {
+fn filter_secrets_list(output: Vec<ListSecretsOutput>, base_secret_names: Vec<String>, env_setting: &EnvSetting, pattern: Option<&NamePattern>) -> Result<NonEmptySecrets, RetrievalError> {
+    // a pattern is explicit, so only exact matches count
+    let full_secret_names = pattern.map(|p| transformations::possible_full_names(&base_secret_names, env_setting, Some(p)));
     let possible_secrets: Vec<String> = output.iter().filter_map(|v| v.secret_list())
         .flatten()
         .filter_map(|v| v.name())
         .map(|v| v.to_string())
         .filter(|v| {
-            match env_setting {
-                EnvSetting::None => is_exact_match_with_base_secret(&base_secret_names, v),
-                EnvSetting::Env(envs) => is_match_with_one_secret_prefixed_with_env(&base_secret_names, v, envs),
+            match (env_setting, &full_secret_names) {
+                (_, Some(full_secret_names)) => full_secret_names.contains(v),
+                (EnvSetting::None, None) => is_exact_match_with_base_secret(&base_secret_names, v),
+                (EnvSetting::Env(envs), None) => is_match_with_one_secret_prefixed_with_env(&base_secret_names, v, envs),
             }
         }).collect();
 
@@ -83,9 +96,9 @@ fn filter_secrets_list(output: Vec<ListSecretsOutput>, base_secret_names: Vec<St
     }
 }
 
-fn is_match_with_one_secret_prefixed_with_env(base_secret_names: &[String], v: &str, envs: &[String]) -> bool {
+fn is_match_with_one_secret_prefixed_with_env(base_secret_names: &[String], v: &str, envs: &[Env]) -> bool {
     base_secret_names.iter()
-        .flat_map(|b| envs.iter().map(|e| format!("/{e}/{b}")).collect::<Vec<String>>())
+        .flat_map(|b| envs.iter().map(|e| format!("/{}/{b}", e.prefix)).collect::<Vec<String>>())
         .any(|combined| v.contains(&combined))
 }
 
@@ -103,9 +116,9 @@ mod tests {
     fn filter_secrets_list_should_find_secret_with_given_prefixed_name() {
         let list = create_secret_list();
         let possible_names = vec!["SampleSecret".to_string(), "sample-secret".to_string(), "sample_secret".to_string()];
-        let env_setting = EnvSetting::Env(vec!["dev".to_string(), "prod".to_string()]);
+        let env_setting = EnvSetting::Env(vec![Env::new("dev", "dev"), Env::new("prod", "prod")]);
 
-        let actual = filter_secrets_list(list, possible_names, &env_setting).unwrap();
+        let actual = filter_secrets_list(list, possible_names, &env_setting, None).unwrap();
 
         assert_eq!(actual.0, vec!["/prod/sample-secret"]);
     }
@@ -116,7 +129,7 @@ mod tests {
         let possible_names = vec!["RealSecret".to_string(), "real-secret".to_string(), "real_secret".to_string()];
         let env_setting = EnvSetting::None;
 
-        let actual = filter_secrets_list(list, possible_names, &env_setting).unwrap();
+        let actual = filter_secrets_list(list, possible_names, &env_setting, None).unwrap();
 
         assert_eq!(actual.0, vec!["RealSecret"]);
     }
@@ -128,19 +141,61 @@ mod tests {
             .secret_list(SecretListEntry::builder().name("/prod/sample-secret").build())
             .build()];
         let possible_names = vec!["SampleSecret".to_string(), "sample-secret".to_string(), "sample_secret".to_string()];
-        let env_setting = EnvSetting::Env(vec!["dev".to_string(), "prod".to_string()]);
+        let env_setting = EnvSetting::Env(vec![Env::new("dev", "dev"), Env::new("prod", "prod")]);
 
-        let actual = filter_secrets_list(list, possible_names, &env_setting).unwrap();
+        let actual = filter_secrets_list(list, possible_names, &env_setting, None).unwrap();
 
         assert_eq!(actual.0, vec!["/prod/sample-secret"]);
     }
 
+    #[test]
+    fn filter_secrets_list_should_find_secret_with_prefix_mapped_from_env() {
+        let list = vec![ListSecretsOutput::builder()
+            .secret_list(SecretListEntry::builder().name("/prod/sample-secret").build())
+            .secret_list(SecretListEntry::builder().name("/production/sample-secret").build())
+            .build()];
+        let possible_names = vec!["SampleSecret".to_string(), "sample-secret".to_string(), "sample_secret".to_string()];
+        let env_setting = EnvSetting::Env(vec![Env::new("prod", "production")]);
+
+        let actual = filter_secrets_list(list, possible_names, &env_setting, None).unwrap();
+
+        assert_eq!(actual.0, vec!["/production/sample-secret"]);
+    }
+
+    #[test]
+    fn filter_secrets_list_should_only_find_exact_matches_with_pattern() {
+        let list = vec![ListSecretsOutput::builder()
+            .secret_list(SecretListEntry::builder().name("app/sample-secret/dev").build())
+            .secret_list(SecretListEntry::builder().name("app/sample-secret/dev/old").build())
+            .secret_list(SecretListEntry::builder().name("/dev/sample-secret").build())
+            .build()];
+        let possible_names = vec!["SampleSecret".to_string(), "sample-secret".to_string(), "sample_secret".to_string()];
+        let env_setting = EnvSetting::Env(vec![Env::new("dev", "dev"), Env::new("prod", "prod")]);
+
+        let actual = filter_secrets_list(list, possible_names, &env_setting, Some(&NamePattern::new("app/{name}/{env}"))).unwrap();
+
+        assert_eq!(actual.0, vec!["app/sample-secret/dev"]);
+    }
+
+    #[test]
+    fn filter_secrets_list_should_only_find_exact_matches_with_default_pattern() {
+        let list = vec![ListSecretsOutput::builder()
+            .secret_list(SecretListEntry::builder().name("/dev/creds").build())
+            .secret_list(SecretListEntry::builder().name("/dev/creds-old").build())
+            .build()];
+        let env_setting = EnvSetting::Env(vec![Env::new("dev", "dev")]);
+
+        let actual = filter_secrets_list(list, vec!["creds".to_string()], &env_setting, Some(&NamePattern::default_for(&env_setting))).unwrap();
+
+        assert_eq!(actual.0, vec!["/dev/creds"]);
+    }
+
     #[test]
     fn filter_secrets_list_should_return_error_for_unknown_secret() {
         let list = create_secret_list();
         let env_setting = EnvSetting::None;
 
-        let actual = filter_secrets_list(list, vec!["Unknown".to_string()], &env_setting);
+        let actual = filter_secrets_list(list, vec!["Unknown".to_string()], &env_setting, None);
 
         assert!(actual.is_err());
     }
@@ -150,7 +205,7 @@ mod tests {
         let list = vec![ListSecretsOutput::builder().build()];
         let env_setting = EnvSetting::None;
 
-        let actual = filter_secrets_list(list, vec!["DoesNotMatter".to_string()], &env_setting);
+        let actual = filter_secrets_list(list, vec!["DoesNotMatter".to_string()], &env_setting, None);
 
         assert!(actual.is_err());
     }
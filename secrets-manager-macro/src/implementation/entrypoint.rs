@@ -1,26 +1,94 @@
 use std::collections::HashMap;
-use proc_macro2::{Ident, TokenStream};
-use syn::{Error, ItemStruct, parse2};
+use proc_macro2::{Ident, Span, TokenStream};
+use syn::{Attribute, Error, ItemStruct, parse2, parse_quote};
+use tokio::runtime::Runtime;
 use syn::spanned::Spanned;
 
 use crate::implementation::aws::SecretsManagerClient;
 use crate::implementation::errors::RetrievalError;
-use crate::implementation::input::{self, EnvSetting};
+use crate::implementation::input::{self, Backend, EnvSetting, SecretGroup, Settings};
+use crate::implementation::local_files::LocalSecrets;
+use crate::implementation::logging;
 use crate::implementation::output;
+use crate::implementation::parameter_store::{ParameterKind, ParameterStoreClient};
 use crate::implementation::transformations;
-use crate::implementation::transformations::ValidatedSecrets;
+use crate::implementation::transformations::{NamePattern, ValidatedSecrets};
+use crate::implementation::vault::VaultClient;
+
+#[cfg_attr(feature = "tracing", tracing::instrument(name = "secret_compile_time_check", skip(env_setting), err))]
+async fn retrieve_real_name_and_keys(base_secret_names: Vec<String>, env_setting: EnvSetting, pattern: Option<NamePattern>, version_stage: Option<String>, backend: Backend) -> Result<(String, HashMap<String, String>), RetrievalError> {
+    if let Some(local_secrets) = LocalSecrets::from_env() {
+        return retrieve_real_name_and_keys_from_files(local_secrets, base_secret_names, env_setting, pattern);
+    }
+    if backend == Backend::Vault {
+        return retrieve_real_name_and_keys_from_vault(base_secret_names, env_setting, pattern).await;
+    }
 
-async fn retrieve_real_name_and_keys(base_secret_names: Vec<String>, env_setting: EnvSetting) -> Result<(String, HashMap<String, String>), RetrievalError> {
     let client = SecretsManagerClient::new().await;
-    let found_secret_names = client.get_filtered_secret_list(base_secret_names, &env_setting).await?;
+    let found_secret_names = client.get_filtered_secret_list(base_secret_names, &env_setting, pattern.as_ref()).await?;
 
-    let validated_secrets = ValidatedSecrets::new(found_secret_names, env_setting)?;
+    let validated_secrets = ValidatedSecrets::new(found_secret_names, env_setting, pattern)?;
     let (full_secret_name, actual_base_name) = validated_secrets.get_full_and_base_secret();
+    #[cfg(feature = "tracing")]
+    tracing::info!(%full_secret_name, "found secret");
+
+    let secret_value = client.get_secret_as_map(&full_secret_name, version_stage.as_deref()).await?;
+    // only one secret is used for the keys, but the stage has to exist in every env
+    if let Some(version_stage) = version_stage.as_deref() {
+        for other_secret_name in validated_secrets.full_secret_names().iter().filter(|s| **s != full_secret_name) {
+            client.get_secret_as_map(other_secret_name, Some(version_stage)).await?;
+        }
+    }
+    Ok((actual_base_name, secret_value))
+}
+
+// local files only have a current version, so the version stage is not checked
+fn retrieve_real_name_and_keys_from_files(local_secrets: LocalSecrets, base_secret_names: Vec<String>, env_setting: EnvSetting, pattern: Option<NamePattern>) -> Result<(String, HashMap<String, String>), RetrievalError> {
+    let found_secret_names = local_secrets.get_filtered_secret_list(base_secret_names, &env_setting, pattern.as_ref())?;
+
+    let validated_secrets = ValidatedSecrets::new(found_secret_names, env_setting, pattern)?;
+    let (full_secret_name, actual_base_name) = validated_secrets.get_full_and_base_secret();
+    #[cfg(feature = "tracing")]
+    tracing::info!(%full_secret_name, "found secret in local files");
+
+    let secret_value = local_secrets.get_secret_as_map(&full_secret_name)?;
+    Ok((actual_base_name, secret_value))
+}
+
+async fn retrieve_real_name_and_keys_from_vault(base_secret_names: Vec<String>, env_setting: EnvSetting, pattern: Option<NamePattern>) -> Result<(String, HashMap<String, String>), RetrievalError> {
+    let client = VaultClient::from_env()?;
+    let found_secret_names = client.get_filtered_secret_list(base_secret_names, &env_setting, pattern.as_ref()).await?;
+
+    let validated_secrets = ValidatedSecrets::new(found_secret_names, env_setting, pattern)?;
+    let (full_secret_name, actual_base_name) = validated_secrets.get_full_and_base_secret();
+    #[cfg(feature = "tracing")]
+    tracing::info!(%full_secret_name, "found secret in Vault");
 
     let secret_value = client.get_secret_as_map(&full_secret_name).await?;
     Ok((actual_base_name, secret_value))
 }
 
+#[cfg_attr(feature = "tracing", tracing::instrument(name = "parameter_compile_time_check", skip(env_setting), err))]
+async fn retrieve_parameter_names(path: String, env_setting: EnvSetting) -> Result<Vec<(String, ParameterKind)>, RetrievalError> {
+    let client = ParameterStoreClient::new().await;
+    let mut paths = transformations::parameter_paths(&path, &env_setting).into_iter();
+    // the first path determines the fields, the others only need to exist
+    let key_path = paths.next().expect("Parameter paths to contain at least one path");
+    let names = client.get_parameter_names(&key_path).await?;
+
+    for other_path in paths {
+        client.get_parameter_names(&other_path).await
+            .map_err(|e| match e {
+                RetrievalError::NotFound(e) => RetrievalError::MissingEnv(e),
+                e => e,
+            })?;
+    }
+    #[cfg(feature = "tracing")]
+    tracing::info!(%key_path, "found parameters");
+
+    Ok(names)
+}
+
 pub fn create_secrets_manager(attributes: TokenStream, item: TokenStream) -> TokenStream {
     let input: ItemStruct = match parse2(item.clone()) {
         Ok(it) => it,
@@ -30,18 +98,102 @@ pub fn create_secrets_manager(attributes: TokenStream, item: TokenStream) -> Tok
         ).into_compile_error(),
     };
 
-    let env_setting = input::get_environments(attributes);
+    let settings = match input::get_settings(attributes) {
+        Ok(it) => it,
+        Err(e) => return e.into_compile_error(),
+    };
 
-    let secret_struct_name = input.ident.to_string();
-    let possible_names = transformations::possible_base_names(&secret_struct_name);
+    let groups = match input::get_secret_groups(&input) {
+        Ok(it) => it,
+        Err(e) => return e.into_compile_error(),
+    };
+    if !groups.is_empty() && settings.name.is_some() {
+        return Error::new(Span::call_site(), "name cannot be combined with #[secret(from = \"...\")] fields").into_compile_error();
+    }
 
+    logging::init();
     let rt = tokio::runtime::Runtime::new().unwrap();
 
-    match rt.block_on(retrieve_real_name_and_keys(possible_names, env_setting.clone())) {
+    if !groups.is_empty() {
+        return create_composed_secrets(&rt, &input, &groups, &settings);
+    }
+
+    let (possible_names, pattern) = match &settings.name {
+        Some(name) => (vec![name.clone()], Some(exact_pattern(&settings))),
+        None => (transformations::possible_base_names(&input.ident.to_string()), settings.pattern.clone()),
+    };
+
+    match rt.block_on(retrieve_real_name_and_keys(possible_names, settings.env_setting.clone(), pattern, settings.version_stage.clone(), settings.backend)) {
         Ok((actual_base_secret_name, key_map)) => {
             let keys: Vec<Ident> = transformations::keys_as_ident_list(key_map);
-            output::create_output(&input, &keys, &actual_base_secret_name, &env_setting)
+            output::create_output(&input, &keys, &actual_base_secret_name, &settings)
+        }
+        Err(e) => e.into_compile_error(input.ident.span())
+    }
+}
+
+// a given name is explicit, so (just like with a pattern) only exact matches count
+fn exact_pattern(settings: &Settings) -> NamePattern {
+    settings.pattern.clone().unwrap_or_else(|| NamePattern::default_for(&settings.env_setting))
+}
+
+// every group is checked against its own secret, and becomes a secrets struct of its own
+fn create_composed_secrets(rt: &Runtime, input: &ItemStruct, groups: &[SecretGroup], settings: &Settings) -> TokenStream {
+    // the composed struct can only derive what its groups derive. Other attributes only apply to the composed struct
+    let derives: Vec<&Attribute> = input.attrs.iter().filter(|a| a.path.is_ident("derive")).collect();
+    let mut group_outputs = vec![];
+    let mut group_struct_names = vec![];
+
+    for group in groups {
+        let group_struct_name = Ident::new(
+            &transformations::group_struct_name(&input.ident.to_string(), &group.field.to_string()),
+            group.field.span(),
+        );
+        let group_item: ItemStruct = parse_quote! {
+            #(#derives)*
+            struct #group_struct_name {}
+        };
+        let group_settings = Settings {
+            env_overrides: settings.env_overrides.for_group(&input.ident.to_string(), &group.field.to_string()),
+            ..settings.clone()
+        };
+
+        match rt.block_on(retrieve_real_name_and_keys(vec![group.secret_name.value()], settings.env_setting.clone(), Some(exact_pattern(settings)), settings.version_stage.clone(), settings.backend)) {
+            Ok((actual_base_secret_name, key_map)) => {
+                let keys: Vec<Ident> = transformations::keys_as_ident_list(key_map);
+                group_outputs.push(output::create_output(&group_item, &keys, &actual_base_secret_name, &group_settings));
+                group_struct_names.push(group_struct_name);
+            }
+            Err(e) => return e.into_compile_error(group.secret_name.span()),
         }
+    }
+
+    let fields: Vec<&Ident> = groups.iter().map(|g| &g.field).collect();
+    output::create_composed_output(input, &fields, &group_struct_names, &group_outputs, settings)
+}
+
+pub fn create_parameter_store(attributes: TokenStream, item: TokenStream) -> TokenStream {
+    let input: ItemStruct = match parse2(item.clone()) {
+        Ok(it) => it,
+        Err(_) => return Error::new(
+            item.span(),
+            "Invalid input received. Expected an empty struct",
+        ).into_compile_error(),
+    };
+
+    let parameter_settings = match input::get_parameter_settings(attributes) {
+        Ok(it) => it,
+        Err(e) => return e.into_compile_error(),
+    };
+
+    logging::init();
+    let rt = tokio::runtime::Runtime::new().unwrap();
+
+    match rt.block_on(retrieve_parameter_names(parameter_settings.path.clone(), parameter_settings.settings.env_setting.clone())) {
+        Ok(names) => match transformations::parameter_names_as_key_list(names, input.ident.span()) {
+            Ok(keys) => output::create_parameters_output(&input, &keys, &parameter_settings),
+            Err(e) => e.into_compile_error(),
+        },
         Err(e) => e.into_compile_error(input.ident.span())
     }
 }
@@ -3,11 +3,13 @@ use std::fmt::{Debug, Display, Formatter};
 
 use aws_sdk_secretsmanager::error::{GetSecretValueError, ListSecretsError};
 use aws_sdk_secretsmanager::types::SdkError;
+use aws_sdk_ssm::error::GetParametersByPathError;
 use proc_macro2::{Span, TokenStream};
 
 #[derive(Debug)]
 pub enum RetrievalError {
     Aws(String),
+    Vault(String),
     NotFound(String),
     MissingEnv(String),
     DuplicateSecrets(String),
@@ -20,14 +22,17 @@ impl RetrievalError {
             RetrievalError::NotFound(e) | RetrievalError::DuplicateSecrets(e) => syn::Error::new(correct_span, e).into_compile_error(),
             RetrievalError::Json => syn::Error::new(correct_span, "could not parse the secret value as JSON").into_compile_error(),
             RetrievalError::MissingEnv(e) => syn::Error::new(correct_span, e).into_compile_error(),
-            RetrievalError::Aws(e) => syn::Error::new(correct_span, e).into_compile_error(),
+            RetrievalError::Aws(e) | RetrievalError::Vault(e) => syn::Error::new(correct_span, e).into_compile_error(),
         }
     }
 }
 
 impl Display for RetrievalError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str("retrieval error")
+        match self {
+            RetrievalError::Json => f.write_str("could not parse the secret value as JSON"),
+            RetrievalError::Aws(e) | RetrievalError::Vault(e) | RetrievalError::NotFound(e) | RetrievalError::MissingEnv(e) | RetrievalError::DuplicateSecrets(e) => f.write_str(e),
+        }
     }
 }
 
@@ -58,4 +63,19 @@ impl From<SdkError<GetSecretValueError>> for RetrievalError {
     }
 }
 
+impl From<SdkError<GetParametersByPathError>> for RetrievalError {
+    fn from(value: SdkError<GetParametersByPathError>) -> Self {
+        match value {
+            SdkError::ServiceError(v) => RetrievalError::Aws(format!(
+                "could not get parameters {} - do you have valid AWS credentials?",
+                v.err()
+                    .message()
+                    .map(|v| format!("({})", v))
+                    .unwrap_or_else(|| "".to_string())
+            )),
+            _ => RetrievalError::Aws(format!("could not get parameters: {}", value)),
+        }
+    }
+}
+
 impl Error for RetrievalError {}
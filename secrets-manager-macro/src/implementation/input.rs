@@ -1,57 +1,422 @@
-use proc_macro2::{Ident, TokenStream};
-use syn::{Token};
+use proc_macro2::{Ident, Span, TokenStream};
+use syn::{Fields, ItemStruct, Lit, LitInt, LitStr, Token, Type};
 use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
 use syn::token::{Comma};
 
+use crate::implementation::transformations::{NamePattern, ENV_PLACEHOLDER, NAME_PLACEHOLDER};
+
+const DEFAULT_ENV_VARIABLES: [&str; 2] = ["ENV", "ENVIRONMENT"];
+// more retries only delay the inevitable, and would eventually overflow the number of attempts
+const MAX_RETRIES: u32 = 10;
+
+struct EnvAttribute {
+    name: Ident,
+    prefix: Option<Ident>,
+}
+
+impl Parse for EnvAttribute {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        let prefix = if input.peek(Token![=>]) {
+            let _arrow: Token![=>] = input.parse()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
+        Ok(EnvAttribute {
+            name,
+            prefix,
+        })
+    }
+}
+
 struct Attributes {
-    _optional_name: Option<Ident>,
-    envs: Punctuated<Ident, Comma>,
+    name: Option<LitStr>,
+    pattern: Option<LitStr>,
+    envs: Punctuated<EnvAttribute, Comma>,
+    env_vars: Punctuated<LitStr, Comma>,
+    default_env: Option<LitStr>,
+    version_stage: Option<LitStr>,
+    retries: Option<LitInt>,
+    timeout_ms: Option<LitInt>,
+    cache_ttl_secs: Option<LitInt>,
+    path: Option<LitStr>,
+    backend: Option<LitStr>,
+    env_overrides: Option<Lit>,
 }
 
 impl Parse for Attributes {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let mut optional_name = None;
-        let mut envs: Punctuated<Ident, Comma> = Punctuated::new();
+        let mut name = None;
+        let mut pattern = None;
+        let mut envs: Punctuated<EnvAttribute, Comma> = Punctuated::new();
+        let mut env_vars: Punctuated<LitStr, Comma> = Punctuated::new();
+        let mut default_env = None;
+        let mut version_stage = None;
+        let mut retries = None;
+        let mut timeout_ms = None;
+        let mut cache_ttl_secs = None;
+        let mut path = None;
+        let mut backend = None;
+        let mut env_overrides = None;
 
         while !input.is_empty() {
             let starting_ident: Ident = input.parse()?;
             let _equals: Token![=] = input.parse()?;
 
-            if starting_ident.to_string().eq("envs") {
-                envs = Punctuated::<Ident, Comma>::parse_terminated(input)?;
-            } else if starting_ident.to_string().eq("name") {
-                optional_name = Some(input.parse()?);
+            match starting_ident.to_string().as_str() {
+                "envs" => envs = parse_list(input)?,
+                "name" => name = Some(input.parse()?),
+                "pattern" => pattern = Some(input.parse()?),
+                "env_var" => env_vars = parse_list(input)?,
+                "default_env" => default_env = Some(input.parse()?),
+                "version_stage" => version_stage = Some(input.parse()?),
+                "retries" => retries = Some(input.parse()?),
+                "timeout_ms" => timeout_ms = Some(input.parse()?),
+                "cache_ttl_secs" => cache_ttl_secs = Some(input.parse()?),
+                "path" => path = Some(input.parse()?),
+                "backend" => backend = Some(input.parse()?),
+                "env_overrides" => env_overrides = Some(input.parse()?),
+                _ => return Err(syn::Error::new(starting_ident.span(), format!("unknown attribute {}", starting_ident))),
+            }
+
+            if !input.is_empty() {
+                let _comma: Comma = input.parse()?;
             }
         }
 
         Ok(Attributes {
-            _optional_name: optional_name,
+            name,
+            pattern,
             envs,
+            env_vars,
+            default_env,
+            version_stage,
+            retries,
+            timeout_ms,
+            cache_ttl_secs,
+            path,
+            backend,
+            env_overrides,
         })
     }
 }
 
+// lists are comma separated, just like the attributes themselves, so stop as soon as the next `key =` shows up
+fn parse_list<T: Parse>(input: ParseStream) -> syn::Result<Punctuated<T, Comma>> {
+    let mut list = Punctuated::new();
+    list.push_value(input.parse()?);
+
+    while input.peek(Comma) && !starts_next_attribute(input) {
+        list.push_punct(input.parse()?);
+        if input.is_empty() {
+            break;
+        }
+        list.push_value(input.parse()?);
+    }
+    Ok(list)
+}
+
+fn starts_next_attribute(input: ParseStream) -> bool {
+    input.peek2(syn::Ident) && input.peek3(Token![=]) && !input.peek3(Token![=>])
+}
+
+struct SecretAttribute {
+    from: LitStr,
+}
+
+impl Parse for SecretAttribute {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let starting_ident: Ident = input.parse()?;
+        if starting_ident != "from" {
+            return Err(syn::Error::new(starting_ident.span(), format!("unknown attribute {}, expected from", starting_ident)));
+        }
+        let _equals: Token![=] = input.parse()?;
+
+        Ok(SecretAttribute {
+            from: input.parse()?,
+        })
+    }
+}
+
+/// An env as it is found in the runtime environment variable, and the prefix of its secret
+#[derive(Clone, Debug, PartialEq)]
+pub struct Env {
+    pub name: String,
+    pub prefix: String,
+}
+
+impl Env {
+    pub fn new(name: &str, prefix: &str) -> Self {
+        Env {
+            name: name.to_string(),
+            prefix: prefix.to_string(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub enum EnvSetting {
     None,
-    Env(Vec<String>)
+    Env(Vec<Env>)
+}
+
+/// Where the generated code looks for the current environment at runtime
+#[derive(Clone)]
+pub struct EnvLookup {
+    pub variables: Vec<String>,
+    pub default: Option<String>,
+}
+
+impl Default for EnvLookup {
+    fn default() -> Self {
+        EnvLookup {
+            variables: DEFAULT_ENV_VARIABLES.iter().map(|v| v.to_string()).collect(),
+            default: None,
+        }
+    }
+}
+
+/// Where the generated code retrieves the secret from at runtime
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Backend {
+    Auto,
+    Sdk,
+    LambdaExtension,
+    Vault,
+    MountedDir,
+}
+
+/// Whether keys can be overridden with environment variables at runtime, and with which prefix
+#[derive(Clone, Debug, PartialEq)]
+pub enum EnvOverrides {
+    Disabled,
+    /// Without a prefix, the uppercase name of the struct is used
+    Enabled { prefix: Option<String> },
+}
+
+impl EnvOverrides {
+    /// The overrides of a secret in a composed struct, named after the composed struct (or its prefix) and the field,
+    /// instead of after the hidden struct of the secret
+    pub fn for_group(&self, composed_struct_name: &str, field: &str) -> EnvOverrides {
+        match self {
+            EnvOverrides::Disabled => EnvOverrides::Disabled,
+            EnvOverrides::Enabled { prefix } => {
+                let prefix = prefix.clone().unwrap_or_else(|| composed_struct_name.to_uppercase());
+                EnvOverrides::Enabled { prefix: Some(format!("{}_{}", prefix, field.to_uppercase())) }
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Settings {
+    pub env_setting: EnvSetting,
+    pub env_lookup: EnvLookup,
+    /// The base name of the secret, instead of the names derived from the struct name
+    pub name: Option<String>,
+    /// How the full secret name is built from the base name and the env, instead of `/{env}/{name}`
+    pub pattern: Option<NamePattern>,
+    pub version_stage: Option<String>,
+    pub retries: Option<u32>,
+    pub timeout_ms: Option<u64>,
+    pub cache_ttl_secs: Option<u64>,
+    pub backend: Backend,
+    pub env_overrides: EnvOverrides,
+}
+
+/// Settings for a struct filled with parameters from Parameter Store
+pub struct ParameterSettings {
+    pub path: String,
+    pub settings: Settings,
+}
+
+/// A field of a composed struct, filled with the secret with the given name
+pub struct SecretGroup {
+    pub field: Ident,
+    pub secret_name: LitStr,
+}
+
+pub fn get_settings(attributes: TokenStream) -> syn::Result<Settings> {
+    let attributes: Attributes = syn::parse2(attributes)?;
+
+    if let Some(path) = &attributes.path {
+        return Err(syn::Error::new(path.span(), "path is only supported by build_parameters_struct"));
+    }
+
+    to_settings(&attributes)
+}
+
+pub fn get_parameter_settings(attributes: TokenStream) -> syn::Result<ParameterSettings> {
+    let attributes: Attributes = syn::parse2(attributes)?;
+
+    if let Some(version_stage) = &attributes.version_stage {
+        return Err(syn::Error::new(version_stage.span(), "version_stage is not supported for parameters"));
+    }
+    if let Some(cache_ttl_secs) = &attributes.cache_ttl_secs {
+        return Err(syn::Error::new(cache_ttl_secs.span(), "cache_ttl_secs is not supported for parameters"));
+    }
+    if let Some(backend) = &attributes.backend {
+        return Err(syn::Error::new(backend.span(), "backend is not supported for parameters"));
+    }
+    if let Some(env_overrides) = &attributes.env_overrides {
+        return Err(syn::Error::new(env_overrides.span(), "env_overrides is not supported for parameters"));
+    }
+    if let Some(name) = &attributes.name {
+        return Err(syn::Error::new(name.span(), "name is not supported for parameters, use path instead"));
+    }
+    if let Some(pattern) = &attributes.pattern {
+        return Err(syn::Error::new(pattern.span(), "pattern is not supported for parameters, use path instead"));
+    }
+
+    let path = match &attributes.path {
+        Some(path) if path.value().starts_with('/') => path.value().trim_end_matches('/').to_string(),
+        Some(path) => return Err(syn::Error::new(path.span(), "path should start with a /")),
+        None => return Err(syn::Error::new(Span::call_site(), "build_parameters_struct needs a path, for example path = \"/app\"")),
+    };
+
+    Ok(ParameterSettings {
+        path,
+        settings: to_settings(&attributes)?,
+    })
 }
 
-pub fn get_environments(attributes: TokenStream) -> EnvSetting {
-    let possible_attributes: Result<Attributes, syn::Error> = syn::parse2(attributes);
+/// The fields marked with `#[secret(from = "...")]`. Empty for a struct that is filled with a single secret
+pub fn get_secret_groups(item: &ItemStruct) -> syn::Result<Vec<SecretGroup>> {
+    let fields = match &item.fields {
+        Fields::Named(fields) => &fields.named,
+        _ => return Ok(vec![]),
+    };
+    if !fields.iter().flat_map(|f| &f.attrs).any(|a| a.path.is_ident("secret")) {
+        return Ok(vec![]);
+    }
 
-    possible_attributes
-        .map(|a| a.envs)
-        .map(|env| env.iter().map(|v| v.to_string()).collect())
-        .map(|v: Vec<String>| {
-            if v.is_empty() {
-                EnvSetting::None
-            } else {
-                EnvSetting::Env(v)
+    fields.iter()
+        .map(|field| {
+            let attribute = field.attrs.iter()
+                .find(|a| a.path.is_ident("secret"))
+                .ok_or_else(|| syn::Error::new(field.span(), "every field of a composed struct needs #[secret(from = \"...\")]"))?;
+            let secret_attribute: SecretAttribute = attribute.parse_args()?;
+            if !matches!(field.ty, Type::Infer(_)) {
+                return Err(syn::Error::new(field.ty.span(), "the type of a composed field is generated, use _ instead"));
             }
+
+            Ok(SecretGroup {
+                field: field.ident.clone().expect("Named fields to have an ident"),
+                secret_name: secret_attribute.from,
+            })
         })
-        .unwrap_or_else(|_| EnvSetting::None)
+        .collect()
+}
+
+fn to_settings(attributes: &Attributes) -> syn::Result<Settings> {
+    let envs: Vec<Env> = attributes.envs.iter()
+        .map(|v| {
+            let name = v.name.to_string();
+            let prefix = v.prefix.as_ref().map(|p| p.to_string()).unwrap_or_else(|| name.clone());
+            Env::new(&name, &prefix)
+        })
+        .collect();
+    let env_setting = if envs.is_empty() {
+        EnvSetting::None
+    } else {
+        EnvSetting::Env(envs)
+    };
+    let env_lookup = get_env_lookup(attributes, &env_setting)?;
+    let retries: Option<u32> = attributes.retries.as_ref().map(|r| r.base10_parse()).transpose()?;
+    if let (Some(retries), Some(lit)) = (retries, &attributes.retries) {
+        if retries > MAX_RETRIES {
+            return Err(syn::Error::new(lit.span(), format!("retries cannot be more than {}", MAX_RETRIES)));
+        }
+    }
+    let timeout_ms = attributes.timeout_ms.as_ref().map(|t| t.base10_parse()).transpose()?;
+    let cache_ttl_secs = attributes.cache_ttl_secs.as_ref().map(|c| c.base10_parse()).transpose()?;
+    let backend = match &attributes.backend {
+        None => Backend::Auto,
+        Some(backend) => match backend.value().as_str() {
+            "auto" => Backend::Auto,
+            "sdk" => Backend::Sdk,
+            "lambda_extension" => Backend::LambdaExtension,
+            "vault" => Backend::Vault,
+            "mounted_dir" => Backend::MountedDir,
+            other => return Err(syn::Error::new(backend.span(), format!("unknown backend {}, expected one of auto, sdk, lambda_extension, vault, mounted_dir", other))),
+        },
+    };
+    match (backend, &attributes.version_stage) {
+        (Backend::Vault, Some(version_stage)) => return Err(syn::Error::new(version_stage.span(), "version_stage is not supported by the vault backend")),
+        (Backend::MountedDir, Some(version_stage)) => return Err(syn::Error::new(version_stage.span(), "version_stage is not supported by the mounted_dir backend")),
+        _ => {}
+    }
+    let name = match &attributes.name {
+        Some(name) if name.value().trim().is_empty() => return Err(syn::Error::new(name.span(), "name should not be empty")),
+        Some(name) => Some(name.value()),
+        None => None,
+    };
+    let pattern = attributes.pattern.as_ref().map(|p| get_pattern(p, &env_setting)).transpose()?;
+    // envs are mounts in Vault, whatever the pattern
+    let pattern = match (backend, &env_setting) {
+        (Backend::Vault, EnvSetting::Env(_)) => pattern.map(|p| p.with_env_as_mount()),
+        _ => pattern,
+    };
+    let env_overrides = match &attributes.env_overrides {
+        None => EnvOverrides::Disabled,
+        Some(Lit::Bool(enabled)) if enabled.value => EnvOverrides::Enabled { prefix: None },
+        Some(Lit::Bool(_)) => EnvOverrides::Disabled,
+        Some(Lit::Str(prefix)) => EnvOverrides::Enabled { prefix: Some(prefix.value()) },
+        Some(other) => return Err(syn::Error::new(other.span(), "env_overrides should be true, false or a prefix like \"MY_APP\"")),
+    };
+
+    Ok(Settings {
+        env_setting,
+        env_lookup,
+        name,
+        pattern,
+        version_stage: attributes.version_stage.as_ref().map(|v| v.value()),
+        retries,
+        timeout_ms,
+        cache_ttl_secs,
+        backend,
+        env_overrides,
+    })
+}
+
+fn get_pattern(pattern: &LitStr, env_setting: &EnvSetting) -> syn::Result<NamePattern> {
+    let value = pattern.value();
+    if value.matches(NAME_PLACEHOLDER).count() != 1 {
+        return Err(syn::Error::new(pattern.span(), "pattern should contain {name} exactly once"));
+    }
+    match (env_setting, value.matches(ENV_PLACEHOLDER).count()) {
+        (EnvSetting::Env(_), 1) | (EnvSetting::None, 0) => Ok(NamePattern::new(&value)),
+        (EnvSetting::Env(_), _) => Err(syn::Error::new(pattern.span(), "pattern should contain {env} exactly once when there are envs")),
+        (EnvSetting::None, _) => Err(syn::Error::new(pattern.span(), "{env} in a pattern only makes sense in combination with envs")),
+    }
+}
+
+fn get_env_lookup(attributes: &Attributes, env_setting: &EnvSetting) -> syn::Result<EnvLookup> {
+    let mut env_lookup = EnvLookup::default();
+
+    if !attributes.env_vars.is_empty() {
+        if let EnvSetting::None = env_setting {
+            return Err(syn::Error::new(attributes.env_vars[0].span(), "env_var only makes sense in combination with envs"));
+        }
+        env_lookup.variables = attributes.env_vars.iter().map(|v| v.value()).collect();
+    }
+
+    if let Some(default_env) = &attributes.default_env {
+        match env_setting {
+            EnvSetting::Env(envs) if envs.iter().any(|e| e.name == default_env.value()) => env_lookup.default = Some(default_env.value()),
+            EnvSetting::Env(envs) => {
+                let names: Vec<&str> = envs.iter().map(|e| e.name.as_str()).collect();
+                return Err(syn::Error::new(default_env.span(), format!("default_env should be one of the envs ({})", names.join(","))));
+            }
+            EnvSetting::None => return Err(syn::Error::new(default_env.span(), "default_env only makes sense in combination with envs")),
+        }
+    }
+
+    Ok(env_lookup)
 }
 
 #[cfg(test)]
@@ -59,10 +424,10 @@ mod tests {
     use proc_macro2::Span;
     use super::*;
     use syn::token::{Eq};
-    use quote::{ToTokens};
+    use quote::{quote, ToTokens};
 
     #[test]
-    fn get_environments_should_return_all_present_envs() {
+    fn get_settings_should_return_all_present_envs() {
         let mut stream = TokenStream::new();
         let mut env_with_equals: Punctuated<Ident, Eq> = Punctuated::new();
         env_with_equals.push(Ident::new("envs", Span::call_site()));
@@ -75,27 +440,341 @@ mod tests {
         envs_separated_by_comma.push(Ident::new("prod", Span::call_site()));
         envs_separated_by_comma.to_tokens(&mut stream);
 
-        let actual = get_environments(stream);
+        let actual = get_settings(stream).unwrap().env_setting;
 
         match actual {
             EnvSetting::Env(actual_vec) => {
                 assert_eq!(actual_vec.len(), 2);
-                assert_eq!(actual_vec[0], "dev".to_string());
-                assert_eq!(actual_vec[1], "prod".to_string());
+                assert_eq!(actual_vec[0], Env::new("dev", "dev"));
+                assert_eq!(actual_vec[1], Env::new("prod", "prod"));
             }
             EnvSetting::None => panic!("Expected ENVS"),
         }
     }
 
     #[test]
-    fn get_environments_should_return_empty_when_for_empty_input() {
+    fn get_settings_should_return_empty_when_for_empty_input() {
         let stream = TokenStream::new();
 
-        let actual = get_environments(stream);
+        let actual = get_settings(stream).unwrap().env_setting;
 
         match actual {
             EnvSetting::None => {}
             EnvSetting::Env(e) => panic!("Expected NONE for env but got {:?}", e),
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn get_settings_should_map_env_names_to_their_prefixes() {
+        let actual = get_settings(quote!(envs = dev => development, prod => production, test)).unwrap().env_setting;
+
+        match actual {
+            EnvSetting::Env(envs) => assert_eq!(envs, vec![Env::new("dev", "development"), Env::new("prod", "production"), Env::new("test", "test")]),
+            EnvSetting::None => panic!("Expected ENVS"),
+        }
+    }
+
+    #[test]
+    fn get_settings_should_accept_default_env_that_is_mapped_to_a_prefix() {
+        let actual = get_settings(quote!(envs = dev => development, prod => production, default_env = "dev")).unwrap().env_lookup;
+
+        assert_eq!(actual.default, Some("dev".to_string()));
+    }
+
+    #[test]
+    fn get_settings_should_use_default_env_variables_when_none_are_given() {
+        let actual = get_settings(quote!(envs = dev,prod)).unwrap().env_lookup;
+
+        assert_eq!(actual.variables, vec!["ENV".to_string(), "ENVIRONMENT".to_string()]);
+        assert_eq!(actual.default, None);
+    }
+
+    #[test]
+    fn get_settings_should_return_envs_followed_by_other_attributes() {
+        let actual = get_settings(quote!(envs = dev,prod, env_var = "APP_STAGE", "STAGE", default_env = "dev")).unwrap();
+
+        match actual.env_setting {
+            EnvSetting::Env(envs) => assert_eq!(envs, vec![Env::new("dev", "dev"), Env::new("prod", "prod")]),
+            EnvSetting::None => panic!("Expected ENVS"),
+        }
+        assert_eq!(actual.env_lookup.variables, vec!["APP_STAGE".to_string(), "STAGE".to_string()]);
+        assert_eq!(actual.env_lookup.default, Some("dev".to_string()));
+    }
+
+    #[test]
+    fn get_settings_should_return_version_stage() {
+        let actual = get_settings(quote!(envs = dev,prod, version_stage = "AWSPREVIOUS")).unwrap();
+
+        assert_eq!(actual.version_stage, Some("AWSPREVIOUS".to_string()));
+    }
+
+    #[test]
+    fn get_settings_should_return_name() {
+        let actual = get_settings(quote!(name = "legacy/payment-service-creds", envs = dev, prod)).unwrap();
+
+        assert_eq!(actual.name, Some("legacy/payment-service-creds".to_string()));
+    }
+
+    #[test]
+    fn get_settings_should_fail_for_empty_name() {
+        let actual = get_settings(quote!(name = ""));
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn get_settings_should_return_pattern() {
+        let actual = get_settings(quote!(envs = dev, prod, pattern = "app/{name}/{env}")).unwrap();
+
+        assert_eq!(actual.pattern, Some(NamePattern::new("app/{name}/{env}")));
+    }
+
+    #[test]
+    fn get_settings_should_use_env_as_mount_in_pattern_for_vault() {
+        let actual = get_settings(quote!(envs = dev, prod, pattern = "app/{name}/{env}", backend = "vault")).unwrap();
+
+        assert_eq!(actual.pattern, Some(NamePattern::new("/{env}/app/{name}")));
+    }
+
+    #[test]
+    fn get_settings_should_fail_for_pattern_without_name() {
+        let actual = get_settings(quote!(envs = dev, prod, pattern = "app/{env}"));
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn get_settings_should_fail_for_pattern_without_env_when_there_are_envs() {
+        let actual = get_settings(quote!(envs = dev, prod, pattern = "app/{name}"));
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn get_settings_should_fail_for_pattern_with_env_without_envs() {
+        let actual = get_settings(quote!(pattern = "{env}-{name}"));
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn get_settings_should_return_retries_and_timeout() {
+        let actual = get_settings(quote!(retries = 5, timeout_ms = 2000)).unwrap();
+
+        assert_eq!(actual.retries, Some(5));
+        assert_eq!(actual.timeout_ms, Some(2000));
+    }
+
+    #[test]
+    fn get_settings_should_return_cache_ttl() {
+        let actual = get_settings(quote!(cache_ttl_secs = 300)).unwrap();
+
+        assert_eq!(actual.cache_ttl_secs, Some(300));
+    }
+
+    #[test]
+    fn get_settings_should_return_auto_backend_by_default() {
+        let actual = get_settings(quote!()).unwrap();
+
+        assert_eq!(actual.backend, Backend::Auto);
+    }
+
+    #[test]
+    fn get_settings_should_return_lambda_extension_backend() {
+        let actual = get_settings(quote!(backend = "lambda_extension")).unwrap();
+
+        assert_eq!(actual.backend, Backend::LambdaExtension);
+    }
+
+    #[test]
+    fn get_settings_should_return_mounted_dir_backend() {
+        let actual = get_settings(quote!(backend = "mounted_dir")).unwrap();
+
+        assert_eq!(actual.backend, Backend::MountedDir);
+    }
+
+    #[test]
+    fn get_settings_should_fail_for_vault_backend_with_version_stage() {
+        let actual = get_settings(quote!(backend = "vault", version_stage = "AWSPREVIOUS"));
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn get_settings_should_fail_for_unknown_backend() {
+        let actual = get_settings(quote!(backend = "carrier_pigeon"));
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn get_settings_should_disable_env_overrides_by_default() {
+        let actual = get_settings(quote!()).unwrap();
+
+        assert_eq!(actual.env_overrides, EnvOverrides::Disabled);
+    }
+
+    #[test]
+    fn get_settings_should_enable_env_overrides_with_default_prefix() {
+        let actual = get_settings(quote!(env_overrides = true)).unwrap();
+
+        assert_eq!(actual.env_overrides, EnvOverrides::Enabled { prefix: None });
+    }
+
+    #[test]
+    fn get_settings_should_enable_env_overrides_with_given_prefix() {
+        let actual = get_settings(quote!(env_overrides = "MY_APP")).unwrap();
+
+        assert_eq!(actual.env_overrides, EnvOverrides::Enabled { prefix: Some("MY_APP".to_string()) });
+    }
+
+    #[test]
+    fn for_group_should_add_field_to_prefix_of_composed_struct() {
+        let default_prefix = EnvOverrides::Enabled { prefix: None };
+        let given_prefix = EnvOverrides::Enabled { prefix: Some("MY_APP".to_string()) };
+
+        assert_eq!(default_prefix.for_group("Services", "db"), EnvOverrides::Enabled { prefix: Some("SERVICES_DB".to_string()) });
+        assert_eq!(given_prefix.for_group("Services", "db"), EnvOverrides::Enabled { prefix: Some("MY_APP_DB".to_string()) });
+        assert_eq!(EnvOverrides::Disabled.for_group("Services", "db"), EnvOverrides::Disabled);
+    }
+
+    #[test]
+    fn get_settings_should_fail_for_numeric_env_overrides() {
+        let actual = get_settings(quote!(env_overrides = 1));
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn get_settings_should_fail_for_too_many_retries() {
+        let actual = get_settings(quote!(retries = 4294967295));
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn get_settings_should_fail_for_negative_retries() {
+        let actual = get_settings(quote!(retries = -1));
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn get_settings_should_fail_for_default_env_that_is_not_one_of_the_envs() {
+        let actual = get_settings(quote!(envs = dev,prod, default_env = "test"));
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn get_settings_should_fail_for_env_var_without_envs() {
+        let actual = get_settings(quote!(env_var = "APP_STAGE"));
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn get_settings_should_fail_for_unknown_attribute() {
+        let actual = get_settings(quote!(unknown = "value"));
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn get_settings_should_fail_for_path() {
+        let actual = get_settings(quote!(path = "/app"));
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn get_parameter_settings_should_return_path_without_trailing_slash_and_envs() {
+        let actual = get_parameter_settings(quote!(path = "/app/", envs = dev, prod)).unwrap();
+
+        assert_eq!(actual.path, "/app");
+        match actual.settings.env_setting {
+            EnvSetting::Env(envs) => assert_eq!(envs, vec![Env::new("dev", "dev"), Env::new("prod", "prod")]),
+            EnvSetting::None => panic!("Expected ENVS"),
+        }
+    }
+
+    #[test]
+    fn get_parameter_settings_should_fail_without_path() {
+        let actual = get_parameter_settings(quote!(envs = dev, prod));
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn get_parameter_settings_should_fail_for_relative_path() {
+        let actual = get_parameter_settings(quote!(path = "app"));
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn get_parameter_settings_should_fail_for_version_stage() {
+        let actual = get_parameter_settings(quote!(path = "/app", version_stage = "AWSPREVIOUS"));
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn get_secret_groups_should_return_field_and_secret_name() {
+        let item: ItemStruct = syn::parse_quote!(struct Services {
+            #[secret(from = "db-credentials")]
+            db: _,
+            #[secret(from = "stripe")]
+            stripe: _,
+        });
+
+        let actual = get_secret_groups(&item).unwrap();
+
+        assert_eq!(actual.len(), 2);
+        assert_eq!(actual[0].field.to_string(), "db");
+        assert_eq!(actual[0].secret_name.value(), "db-credentials");
+        assert_eq!(actual[1].field.to_string(), "stripe");
+        assert_eq!(actual[1].secret_name.value(), "stripe");
+    }
+
+    #[test]
+    fn get_secret_groups_should_return_empty_for_empty_struct() {
+        let item: ItemStruct = syn::parse_quote!(struct Services {});
+
+        let actual = get_secret_groups(&item).unwrap();
+
+        assert!(actual.is_empty());
+    }
+
+    #[test]
+    fn get_secret_groups_should_fail_for_field_without_secret_attribute() {
+        let item: ItemStruct = syn::parse_quote!(struct Services {
+            #[secret(from = "db-credentials")]
+            db: _,
+            stripe: _,
+        });
+
+        let actual = get_secret_groups(&item);
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn get_secret_groups_should_fail_for_field_with_type() {
+        let item: ItemStruct = syn::parse_quote!(struct Services {
+            #[secret(from = "db-credentials")]
+            db: String,
+        });
+
+        let actual = get_secret_groups(&item);
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn get_parameter_settings_should_fail_for_name() {
+        let actual = get_parameter_settings(quote!(path = "/app", name = "app"));
+
+        assert!(actual.is_err());
+    }
+}
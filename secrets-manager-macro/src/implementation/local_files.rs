@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::implementation::aws::NonEmptySecrets;
+use crate::implementation::errors::RetrievalError;
+use crate::implementation::input::EnvSetting;
+use crate::implementation::transformations::{self, NamePattern};
+
+const DEFAULT_DIR: &str = ".secrets";
+
+/// Secrets in JSON files in a local directory, used instead of AWS when `SECRETS_SOURCE` is `file`
+pub struct LocalSecrets {
+    dir: PathBuf,
+}
+
+impl LocalSecrets {
+    pub fn from_env() -> Option<Self> {
+        match std::env::var("SECRETS_SOURCE") {
+            Ok(source) if source == "file" => {
+                let dir = std::env::var("SECRETS_DIR").unwrap_or_else(|_| DEFAULT_DIR.to_string());
+                // relative to the crate that uses the macro, not to wherever the compiler happens to run
+                let dir = match std::env::var("CARGO_MANIFEST_DIR") {
+                    Ok(manifest_dir) => Path::new(&manifest_dir).join(dir),
+                    Err(_) => PathBuf::from(dir),
+                };
+                Some(LocalSecrets { dir })
+            }
+            _ => None,
+        }
+    }
+
+    pub fn get_filtered_secret_list(&self, base_secret_names: Vec<String>, env_setting: &EnvSetting, pattern: Option<&NamePattern>) -> Result<NonEmptySecrets, RetrievalError> {
+        let possible_secrets: Vec<String> = transformations::possible_full_names(&base_secret_names, env_setting, pattern)
+            .into_iter()
+            .filter(|name| self.path(name).is_file())
+            .collect();
+
+        if possible_secrets.is_empty() {
+            Err(RetrievalError::NotFound(format!(
+                "Could not find a file in {} for secret with any of these names: {}",
+                self.dir.display(),
+                base_secret_names.join(",")
+            )))
+        } else {
+            Ok(NonEmptySecrets(possible_secrets))
+        }
+    }
+
+    pub fn get_secret_as_map(&self, full_secret_name: &str) -> Result<HashMap<String, String>, RetrievalError> {
+        let path = self.path(full_secret_name);
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| RetrievalError::NotFound(format!("Could not read file {}: {}", path.display(), e)))?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    fn path(&self, secret_name: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", secret_name.trim_start_matches('/')))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::implementation::input::Env;
+
+    use super::*;
+
+    #[test]
+    fn get_filtered_secret_list_should_only_return_existing_files() {
+        let dir = std::env::temp_dir().join(format!("secrets-manager-macro-compile-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("dev")).unwrap();
+        std::fs::write(dir.join("dev").join("sample-secret.json"), r#"{ "key1": "value1" }"#).unwrap();
+        let local_secrets = LocalSecrets { dir: dir.clone() };
+        let env_setting = EnvSetting::Env(vec![Env::new("dev", "dev"), Env::new("prod", "prod")]);
+
+        let actual = local_secrets.get_filtered_secret_list(vec!["sample-secret".to_string()], &env_setting, None).unwrap();
+        let values = local_secrets.get_secret_as_map("/dev/sample-secret").unwrap();
+
+        assert_eq!(actual.0, vec!["/dev/sample-secret"]);
+        assert_eq!(values.get("key1").unwrap(), "value1");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
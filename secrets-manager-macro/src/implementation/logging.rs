@@ -0,0 +1,18 @@
+// compile time logging only happens when the tracing feature is enabled *and* this env var is set,
+// to avoid cluttering every build
+#[cfg(feature = "tracing")]
+const LOG_ENV_VAR: &str = "SECRETS_MANAGER_MACRO_LOG";
+
+#[cfg(feature = "tracing")]
+pub fn init() {
+    if std::env::var(LOG_ENV_VAR).is_ok() {
+        // a subscriber might already be set by an earlier invocation of the macro
+        let _ = tracing_subscriber::fmt()
+            .with_writer(std::io::stderr)
+            .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
+            .try_init();
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+pub fn init() {}
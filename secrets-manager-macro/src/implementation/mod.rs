@@ -2,5 +2,9 @@ mod aws;
 pub mod entrypoint;
 mod errors;
 mod output;
+mod parameter_store;
 mod transformations;
+mod vault;
 mod input;
+mod local_files;
+mod logging;
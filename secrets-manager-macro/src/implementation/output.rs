@@ -1,122 +1,756 @@
 use proc_macro2::{Ident, TokenStream};
 use quote::{format_ident, quote};
 use syn::ItemStruct;
-use crate::implementation::input::EnvSetting;
+use crate::implementation::input::{Backend, Env, EnvLookup, EnvOverrides, EnvSetting, ParameterSettings, Settings};
+use crate::implementation::parameter_store::ParameterKind;
+use crate::implementation::transformations::{NamePattern, ParameterKey, ENV_PLACEHOLDER};
+
+fn get_secret_string_name(secret_struct_name: &Ident) -> Ident {
+    format_ident!("{}SecretString", secret_struct_name.to_string())
+}
+
+// double underscore to avoid clashes with the keys of the secret
+fn get_metadata_field() -> Ident {
+    format_ident!("__metadata")
+}
+
+// looks up the env at runtime and maps it to its prefix, leaving it in `env`
+fn create_find_env(envs: &[Env], env_lookup: &EnvLookup) -> TokenStream {
+    let env_variables = &env_lookup.variables;
+    let fallback = match &env_lookup.default {
+        Some(default_env) => quote! {
+            .unwrap_or_else(|| #default_env.to_string())
+        },
+        None => quote! {
+            .ok_or_else(|| ::secrets_manager_macro_runtime::Error::MissingEnv(vec![#(#env_variables.to_string()),*]))?
+        },
+    };
+
+    let aliased_envs: Vec<&Env> = envs.iter().filter(|e| e.name != e.prefix).collect();
+    let map_to_prefix = if aliased_envs.is_empty() {
+        quote!()
+    } else {
+        let names = aliased_envs.iter().map(|e| &e.name);
+        let prefixes = aliased_envs.iter().map(|e| &e.prefix);
+        quote! {
+            let env = match env.as_str() {
+                #(#names => #prefixes.to_string(),)*
+                _ => env,
+            };
+        }
+    };
 
-// there are libraries for secret strings, but this way no additional import is needed (there are enough already)
-fn create_secret_string_struct(secret_string_name: &Ident) -> TokenStream {
     quote! {
-        #[derive(Clone,PartialEq)]
-        pub struct #secret_string_name(String);
+        let env = ::secrets_manager_macro_runtime::find_env(&[#(#env_variables),*])#fallback;
+        #map_to_prefix
+    }
+}
 
-        impl #secret_string_name {
-            pub fn new(input: String) -> Self {
-                #secret_string_name(input)
-            }
+fn get_retries_and_timeout(settings: &Settings) -> (TokenStream, TokenStream) {
+    let retries = match settings.retries {
+        Some(retries) => quote!(Some(#retries)),
+        None => quote!(None),
+    };
+    let timeout = match settings.timeout_ms {
+        Some(timeout_ms) => quote!(Some(std::time::Duration::from_millis(#timeout_ms))),
+        None => quote!(None),
+    };
+    (retries, timeout)
+}
+
+fn create_load_config(settings: &Settings) -> TokenStream {
+    let (retries, timeout) = get_retries_and_timeout(settings);
+
+    quote! {
+        async fn load_config() -> ::secrets_manager_macro_runtime::aws_config::SdkConfig {
+            ::secrets_manager_macro_runtime::load_config(#retries, #timeout).await
         }
+    }
+}
 
-        impl std::fmt::Debug for #secret_string_name {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                f.write_str(&format!("{}****", self.0.chars().next().unwrap_or('*')))
-            }
+fn create_secrets_client(settings: &Settings) -> TokenStream {
+    let (retries, timeout) = get_retries_and_timeout(settings);
+    let backend = match settings.backend {
+        Backend::Auto => quote!(::secrets_manager_macro_runtime::Backend::Auto),
+        Backend::Sdk => quote!(::secrets_manager_macro_runtime::Backend::Sdk),
+        Backend::LambdaExtension => quote!(::secrets_manager_macro_runtime::Backend::LambdaExtension),
+        Backend::Vault => quote!(::secrets_manager_macro_runtime::Backend::Vault),
+        Backend::MountedDir => quote!(::secrets_manager_macro_runtime::Backend::MountedDir),
+    };
+
+    quote! {
+        async fn client() -> ::secrets_manager_macro_runtime::SecretsClient {
+            ::secrets_manager_macro_runtime::SecretsClient::new(#backend, #retries, #timeout).await
         }
+    }
+}
 
-        impl AsRef<str> for #secret_string_name {
-            fn as_ref(&self) -> &str {
-                &self.0
-            }
+// the configured backends need a tokio reactor, which `on_runtime` provides on any executor
+fn on_runtime(body: TokenStream) -> TokenStream {
+    quote! {
+        ::secrets_manager_macro_runtime::on_runtime(async move {
+            #body
+        }).await
+    }
+}
+
+// a source or config passed by the caller stays on the executor of the caller
+fn on_runtime_for_backend(retrieve: TokenStream) -> TokenStream {
+    let retrieve_on_runtime = on_runtime(retrieve.clone());
+
+    quote! {
+        if origin.backend.is_some() {
+            #retrieve_on_runtime
+        } else {
+            #retrieve
         }
     }
 }
 
-fn get_secret_string_name(secret_struct_name: &Ident) -> Ident {
-    format_ident!("{}SecretString", secret_struct_name.to_string())
+fn create_never_retrieved_error() -> TokenStream {
+    quote! {
+        Err(::secrets_manager_macro_runtime::Error::Runtime("values that were never retrieved can only be retrieved with the rt-tokio feature".to_string()))
+    }
+}
+
+fn create_invalidate_cache(settings: &Settings) -> TokenStream {
+    match settings.cache_ttl_secs {
+        Some(_) => quote! {
+            if let Ok((secret_name, _)) = Self::secret_name_and_env() {
+                ::secrets_manager_macro_runtime::SecretCache::global().invalidate(&secret_name);
+            }
+        },
+        None => quote!(),
+    }
+}
+
+// only values from the configured backend can be in the cache
+fn create_invalidate_cache_of_origin(settings: &Settings) -> TokenStream {
+    let invalidate_cache = create_invalidate_cache(settings);
+
+    match settings.cache_ttl_secs {
+        Some(_) => quote! {
+            if origin.backend.is_some() {
+                #invalidate_cache
+            }
+        },
+        None => quote!(),
+    }
 }
 
-fn create_init_for_secrets(keys: &[Ident], secret_struct_name: &Ident, actual_base_secret_name: &str, env_setting: &EnvSetting) -> TokenStream {
+fn get_default_version(settings: &Settings) -> TokenStream {
+    match &settings.version_stage {
+        Some(stage) => quote!(::secrets_manager_macro_runtime::Version::Stage(#stage.to_string())),
+        None => quote!(::secrets_manager_macro_runtime::Version::Current),
+    }
+}
+
+fn create_init_for_secrets(keys: &[Ident], secret_struct_name: &Ident, actual_base_secret_name: &str, settings: &Settings) -> TokenStream {
     let secret_string_name = get_secret_string_name(secret_struct_name);
 
     let init_of_struct = keys.iter().map(|k| {
         quote! {
-            #k: #secret_string_name::new(map.get(stringify!(#k)).expect(&format!("Expected key {} to be present", stringify!(#k))).to_string())
+            #k: #secret_string_name::new(secret.get(stringify!(#k))?)
         }
     });
 
-    let build_secret_name = match env_setting {
+    let metadata_field = get_metadata_field();
+    let default_version = get_default_version(settings);
+
+    let env_for_context = match &settings.env_setting {
+        EnvSetting::None => quote!(None),
+        EnvSetting::Env(_) => quote!(Some(env)),
+    };
+
+    let fetch_secret = match settings.cache_ttl_secs {
+        Some(ttl) => quote! {
+            let fetch = || ::secrets_manager_macro_runtime::instrumented(&context, ::secrets_manager_macro_runtime::SecretSource::fetch_version(&*origin.source, &secret_name, &origin.version));
+            // values from a source or config passed by the caller never end up in the cache
+            let secret = match origin.backend {
+                Some(backend) => ::secrets_manager_macro_runtime::SecretCache::global()
+                    .get_or_fetch(backend, &secret_name, &origin.version, std::time::Duration::from_secs(#ttl), fetch)
+                    .await?,
+                None => fetch().await?,
+            };
+        },
+        None => quote! {
+            let secret = ::secrets_manager_macro_runtime::instrumented(&context, ::secrets_manager_macro_runtime::SecretSource::fetch_version(&*origin.source, &secret_name, &origin.version)).await?;
+        },
+    };
+    let invalidate_cache = create_invalidate_cache_of_origin(settings);
+
+    let override_from_env = match &settings.env_overrides {
+        EnvOverrides::Disabled => quote!(),
+        EnvOverrides::Enabled { prefix } => {
+            let prefix = prefix.clone().unwrap_or_else(|| secret_struct_name.to_string().to_uppercase());
+            quote! {
+                let secret = secret.with_env_overrides(#prefix, &[#(stringify!(#keys)),*]);
+            }
+        }
+    };
+
+    let pattern = settings.pattern.clone().unwrap_or_else(|| NamePattern::default_for(&settings.env_setting));
+    let build_secret_name = match &settings.env_setting {
         EnvSetting::None => {
+            let full_secret_name = pattern.full_name(actual_base_secret_name, None);
+
             quote! {
-                let secret_name = #actual_base_secret_name;
+                let secret_name = #full_secret_name;
             }
         }
-        EnvSetting::Env(_) => {
+        EnvSetting::Env(envs) => {
+            let find_env = create_find_env(envs, &settings.env_lookup);
+            let secret_name_without_env = pattern.with_name(actual_base_secret_name);
+
             quote! {
-                let env_vec: Vec<String> = ["ENV", "ENVIRONMENT"].iter().flat_map(std::env::var).collect();
-                let secret_name = env_vec
-                    .first()
-                    .map(|env| format!("/{}/{}", env, #actual_base_secret_name))
-                    .expect("Expected environment variable 'ENV' or 'ENVIRONMENT' to be present");
+                #find_env
+                let secret_name = #secret_name_without_env.replace(#ENV_PLACEHOLDER, &env);
             }
         }
     };
 
+    let backend_block = if cfg!(feature = "rt-tokio") {
+        create_backend_constructors_for_secrets(settings)
+    } else {
+        quote!()
+    };
+    let (refetch, retrieve_again) = if cfg!(feature = "rt-tokio") {
+        (
+            on_runtime_for_backend(quote!(Self::retrieve(origin).await)),
+            quote!(Self::try_new().await),
+        )
+    } else {
+        (quote!(Self::retrieve(origin).await), create_never_retrieved_error())
+    };
+
+    quote! {
+        impl #secret_struct_name {
+            #backend_block
+
+            /// Like `try_new`, but with your own AWS config (for example with an HTTP connector for another async runtime)
+            pub async fn try_new_with_config(shared_config: &::secrets_manager_macro_runtime::aws_config::SdkConfig) -> Result<Self, ::secrets_manager_macro_runtime::Error> {
+                let client = std::sync::Arc::new(::secrets_manager_macro_runtime::aws_sdk_secretsmanager::Client::new(shared_config));
+                Self::retrieve(::secrets_manager_macro_runtime::Origin::new(client, #default_version)).await
+            }
+
+            /// Like `new`, but retrieves the secret from the given source instead of the configured backend, bypassing the cache
+            pub async fn new_from_source(source: impl ::secrets_manager_macro_runtime::SecretSource + 'static) -> Self {
+                Self::try_new_from_source(source).await.unwrap_or_else(|e| panic!("Could not retrieve secret: {}", e))
+            }
+
+            pub async fn try_new_from_source(source: impl ::secrets_manager_macro_runtime::SecretSource + 'static) -> Result<Self, ::secrets_manager_macro_runtime::Error> {
+                Self::retrieve(::secrets_manager_macro_runtime::Origin::new(std::sync::Arc::new(source), #default_version)).await
+            }
+
+            fn secret_name_and_env() -> Result<(String, Option<String>), ::secrets_manager_macro_runtime::Error> {
+                #build_secret_name
+                Ok((secret_name.to_string(), #env_for_context))
+            }
+
+            async fn retrieve(origin: ::secrets_manager_macro_runtime::Origin) -> Result<Self, ::secrets_manager_macro_runtime::Error> {
+                let (secret_name, env) = Self::secret_name_and_env()?;
+
+                let context = ::secrets_manager_macro_runtime::FetchContext {
+                    struct_name: stringify!(#secret_struct_name),
+                    secret_name: &secret_name,
+                    env: env.as_deref(),
+                };
+                #fetch_secret
+                #override_from_env
+
+                Ok(#secret_struct_name {
+                    #(#init_of_struct,)*
+                    #metadata_field: ::secrets_manager_macro_runtime::Metadata::new(secret.version_id().map(|v| v.to_string()), Some(origin)),
+                })
+            }
+
+            // the same version from the same source (or config) as the current values.
+            // A (derived) default was never retrieved, so it is retrieved like `try_new`
+            async fn refreshed(&self) -> Result<Self, ::secrets_manager_macro_runtime::Error> {
+                match self.#metadata_field.origin() {
+                    Some(origin) => {
+                        #invalidate_cache
+                        let origin = origin.clone();
+                        #refetch
+                    }
+                    None => #retrieve_again,
+                }
+            }
+
+            /// Retrieves the secret again, replacing all values (for example after a rotation)
+            pub async fn refresh(&mut self) {
+                self.try_refresh().await.unwrap_or_else(|e| panic!("Could not retrieve secret: {}", e))
+            }
+
+            /// Like `refresh`, but returns an error instead of panicking, keeping the current values
+            pub async fn try_refresh(&mut self) -> Result<(), ::secrets_manager_macro_runtime::Error> {
+                *self = self.refreshed().await?;
+                Ok(())
+            }
+
+            /// The moment the current values were retrieved
+            pub fn fetched_at(&self) -> std::time::SystemTime {
+                self.#metadata_field.fetched_at()
+            }
+
+            /// The AWS version id of the current values
+            pub fn version_id(&self) -> Option<&str> {
+                self.#metadata_field.version_id()
+            }
+        }
+    }
+}
+
+// only with tokio, which the SDK needs for its default connector and the Lambda extension and Vault for hyper
+fn create_backend_constructors_for_secrets(settings: &Settings) -> TokenStream {
+    let secrets_client = create_secrets_client(settings);
+    let default_version = get_default_version(settings);
+    let retrieve_from_backend = on_runtime(quote! {
+        Self::retrieve(::secrets_manager_macro_runtime::Origin::configured(Self::client().await, version)).await
+    });
+    let retrieve_current_and_previous = on_runtime(quote! {
+        let origin = ::secrets_manager_macro_runtime::Origin::configured(Self::client().await, ::secrets_manager_macro_runtime::Version::Current);
+        let current = Self::retrieve(origin.clone()).await?;
+        // a secret that was never rotated has no previous version
+        let previous = match Self::retrieve(origin.at(::secrets_manager_macro_runtime::Version::Stage("AWSPREVIOUS".to_string()))).await {
+            Ok(previous) => Some(previous),
+            Err(::secrets_manager_macro_runtime::Error::NotFound(_)) => None,
+            Err(e) => return Err(e),
+        };
+
+        Ok(::secrets_manager_macro_runtime::CurrentAndPrevious {
+            current,
+            previous,
+        })
+    });
+
     quote! {
-        async fn get_secret(
-                client: &aws_sdk_secretsmanager::Client,
-                secret_name: &str,
-        ) -> aws_sdk_secretsmanager::output::GetSecretValueOutput {
-            client
-                .get_secret_value()
-                .secret_id(secret_name)
-                .send()
-                .await
-                .expect(&format!("Expected to find secret with name {}. Instead got error", secret_name))
-        }
-
-        fn get_secret_as_map(
-            output: aws_sdk_secretsmanager::output::GetSecretValueOutput,
-        ) -> std::collections::HashMap<String, String> {
-            let content = output
-                .secret_string()
-                .map_or_else(|| "{}".to_string(), |v| v.to_string());
-            serde_json::from_str(&content).expect("Expected to be able to parse the secret value")
+        pub async fn new() -> Self {
+            Self::try_new().await.unwrap_or_else(|e| panic!("Could not retrieve secret: {}", e))
         }
 
+        /// Like `new`, but returns an error instead of panicking when the secret cannot be retrieved
+        pub async fn try_new() -> Result<Self, ::secrets_manager_macro_runtime::Error> {
+            Self::retrieve_from_backend(#default_version).await
+        }
+
+        /// Like `new`, but retrieves the version with the given stage (for example `AWSPREVIOUS` or `AWSPENDING`)
+        pub async fn new_at_stage(stage: &str) -> Self {
+            Self::try_new_at_stage(stage).await.unwrap_or_else(|e| panic!("Could not retrieve secret: {}", e))
+        }
+
+        pub async fn try_new_at_stage(stage: &str) -> Result<Self, ::secrets_manager_macro_runtime::Error> {
+            Self::retrieve_from_backend(::secrets_manager_macro_runtime::Version::Stage(stage.to_string())).await
+        }
+
+        /// Like `new`, but retrieves the version with the given id
+        pub async fn new_at_version(version_id: &str) -> Self {
+            Self::try_new_at_version(version_id).await.unwrap_or_else(|e| panic!("Could not retrieve secret: {}", e))
+        }
+
+        pub async fn try_new_at_version(version_id: &str) -> Result<Self, ::secrets_manager_macro_runtime::Error> {
+            Self::retrieve_from_backend(::secrets_manager_macro_runtime::Version::Id(version_id.to_string())).await
+        }
+
+        /// Retrieves both the `AWSCURRENT` and `AWSPREVIOUS` version, for use during a rotation
+        pub async fn load_current_and_previous() -> ::secrets_manager_macro_runtime::CurrentAndPrevious<Self> {
+            Self::try_load_current_and_previous().await.unwrap_or_else(|e| panic!("Could not retrieve secret: {}", e))
+        }
+
+        pub async fn try_load_current_and_previous() -> Result<::secrets_manager_macro_runtime::CurrentAndPrevious<Self>, ::secrets_manager_macro_runtime::Error> {
+            #retrieve_current_and_previous
+        }
+
+        #secrets_client
+
+        async fn retrieve_from_backend(version: ::secrets_manager_macro_runtime::Version) -> Result<Self, ::secrets_manager_macro_runtime::Error> {
+            #retrieve_from_backend
+        }
+    }
+}
+
+fn get_handle_name(secret_struct_name: &Ident) -> Ident {
+    format_ident!("{}Handle", secret_struct_name.to_string())
+}
+
+fn create_blocking_for_secrets(secret_struct_name: &Ident) -> TokenStream {
+    quote! {
+        impl #secret_struct_name {
+            /// Like `new`, but for applications that are not async
+            pub fn new_blocking() -> Self {
+                Self::try_new_blocking().unwrap_or_else(|e| panic!("Could not retrieve secret: {}", e))
+            }
+
+            /// Like `try_new`, but for applications that are not async
+            pub fn try_new_blocking() -> Result<Self, ::secrets_manager_macro_runtime::Error> {
+                ::secrets_manager_macro_runtime::block_on(Self::try_new)
+            }
+        }
+    }
+}
+
+fn create_singleton_for_secrets(secret_struct_name: &Ident) -> TokenStream {
+    quote! {
+        impl #secret_struct_name {
+            /// Retrieves the secret once per process. Concurrent first callers share a single request
+            pub async fn get() -> &'static #secret_struct_name {
+                static INSTANCE: ::secrets_manager_macro_runtime::tokio::sync::OnceCell<#secret_struct_name> = ::secrets_manager_macro_runtime::tokio::sync::OnceCell::const_new();
+                INSTANCE.get_or_init(Self::new).await
+            }
+        }
+    }
+}
+
+fn create_watch_for_secrets(secret_struct_name: &Ident, settings: &Settings) -> TokenStream {
+    let handle_name = get_handle_name(secret_struct_name);
+    let invalidate_cache = create_invalidate_cache(settings);
+
+    quote! {
+        pub type #handle_name = ::secrets_manager_macro_runtime::Handle<#secret_struct_name>;
+
         impl #secret_struct_name {
+            /// Retrieves the secret and keeps refreshing it every `ttl` in a background task.
+            /// When a refresh fails, the last good value is kept
+            pub async fn watch(ttl: std::time::Duration) -> #handle_name {
+                Self::try_watch(ttl).await.unwrap_or_else(|e| panic!("Could not retrieve secret: {}", e))
+            }
+
+            /// Like `watch`, but returns an error instead of panicking when the initial retrieval fails
+            pub async fn try_watch(ttl: std::time::Duration) -> Result<#handle_name, ::secrets_manager_macro_runtime::Error> {
+                ::secrets_manager_macro_runtime::watch(Self::retrieve_latest().await?, ttl, Self::retrieve_latest, Self::version_id)
+            }
+
+            // drops the cached value first, which would be stale when the ttl of the cache is longer than the one of the watch
+            async fn retrieve_latest() -> Result<Self, ::secrets_manager_macro_runtime::Error> {
+                #invalidate_cache
+                Self::try_new().await
+            }
+        }
+    }
+}
+
+// only secure strings are hidden behind a secret string
+fn get_parameter_field_type(key: &ParameterKey, secret_string_name: &Ident) -> TokenStream {
+    match key.kind {
+        ParameterKind::String => quote!(String),
+        ParameterKind::StringList => quote!(Vec<String>),
+        ParameterKind::SecureString => quote!(#secret_string_name),
+    }
+}
+
+fn create_init_for_parameters(keys: &[ParameterKey], parameters_struct_name: &Ident, parameter_settings: &ParameterSettings) -> TokenStream {
+    let secret_string_name = get_secret_string_name(parameters_struct_name);
+    let settings = &parameter_settings.settings;
+    let path = &parameter_settings.path;
+
+    let init_of_struct = keys.iter().map(|key| {
+        let field = &key.field;
+        let name = &key.name;
+        match key.kind {
+            ParameterKind::String => quote! {
+                #field: parameters.get(#name)?
+            },
+            ParameterKind::StringList => quote! {
+                #field: ::secrets_manager_macro_runtime::split_string_list(&parameters.get(#name)?)
+            },
+            ParameterKind::SecureString => quote! {
+                #field: #secret_string_name::new(parameters.get(#name)?)
+            },
+        }
+    });
+
+    let metadata_field = get_metadata_field();
+    let backend_block = if cfg!(feature = "rt-tokio") {
+        let load_config = create_load_config(settings);
+        let retrieve_from_backend = on_runtime(quote! {
+            let client = ::secrets_manager_macro_runtime::aws_sdk_ssm::Client::new(&Self::load_config().await);
+            Self::retrieve(::secrets_manager_macro_runtime::Origin::configured_parameter_store(client)).await
+        });
+
+        quote! {
             pub async fn new() -> Self {
-                let shared_config = aws_config::from_env().load().await;
-                let client = aws_sdk_secretsmanager::Client::new(&shared_config);
+                Self::try_new().await.unwrap_or_else(|e| panic!("Could not retrieve parameters: {}", e))
+            }
 
-                #build_secret_name
+            /// Like `new`, but returns an error instead of panicking when the parameters cannot be retrieved
+            pub async fn try_new() -> Result<Self, ::secrets_manager_macro_runtime::Error> {
+                #retrieve_from_backend
+            }
+
+            #load_config
+        }
+    } else {
+        quote!()
+    };
+    let (refetch, retrieve_again) = if cfg!(feature = "rt-tokio") {
+        (
+            on_runtime_for_backend(quote!(Self::retrieve(origin).await)),
+            quote!(Self::try_new().await),
+        )
+    } else {
+        (quote!(Self::retrieve(origin).await), create_never_retrieved_error())
+    };
+
+    let build_path = match &settings.env_setting {
+        EnvSetting::None => quote! {
+            Ok((#path.to_string(), None))
+        },
+        EnvSetting::Env(envs) => {
+            let find_env = create_find_env(envs, &settings.env_lookup);
+
+            quote! {
+                #find_env
+                Ok((format!("{}/{}", #path, env), Some(env)))
+            }
+        }
+    };
 
-                let secret_value = get_secret(&client, &secret_name).await;
-                let map = get_secret_as_map(secret_value);
+    quote! {
+        impl #parameters_struct_name {
+            #backend_block
+
+            /// Like `try_new`, but with your own AWS config (for example with an HTTP connector for another async runtime)
+            pub async fn try_new_with_config(shared_config: &::secrets_manager_macro_runtime::aws_config::SdkConfig) -> Result<Self, ::secrets_manager_macro_runtime::Error> {
+                let client = ::secrets_manager_macro_runtime::aws_sdk_ssm::Client::new(shared_config);
+                Self::retrieve(::secrets_manager_macro_runtime::Origin::parameter_store(client)).await
+            }
+
+            fn path_and_env() -> Result<(String, Option<String>), ::secrets_manager_macro_runtime::Error> {
+                #build_path
+            }
 
-                #secret_struct_name {
+            async fn retrieve(origin: ::secrets_manager_macro_runtime::Origin) -> Result<Self, ::secrets_manager_macro_runtime::Error> {
+                let (path, env) = Self::path_and_env()?;
+
+                let context = ::secrets_manager_macro_runtime::FetchContext {
+                    struct_name: stringify!(#parameters_struct_name),
+                    secret_name: &path,
+                    env: env.as_deref(),
+                };
+                let parameters = ::secrets_manager_macro_runtime::instrumented(&context, ::secrets_manager_macro_runtime::SecretSource::fetch_version(&*origin.source, &path, &origin.version)).await?;
+
+                Ok(#parameters_struct_name {
                     #(#init_of_struct,)*
-                }
+                    #metadata_field: ::secrets_manager_macro_runtime::Metadata::new(None, Some(origin)),
+                })
+            }
+
+            /// Retrieves the parameters again with the same client, replacing all values
+            pub async fn refresh(&mut self) {
+                self.try_refresh().await.unwrap_or_else(|e| panic!("Could not retrieve parameters: {}", e))
+            }
+
+            /// Like `refresh`, but returns an error instead of panicking, keeping the current values
+            pub async fn try_refresh(&mut self) -> Result<(), ::secrets_manager_macro_runtime::Error> {
+                *self = match self.#metadata_field.origin() {
+                    Some(origin) => {
+                        let origin = origin.clone();
+                        #refetch
+                    }
+                    None => #retrieve_again,
+                }?;
+                Ok(())
+            }
+
+            /// The moment the current values were retrieved
+            pub fn fetched_at(&self) -> std::time::SystemTime {
+                self.#metadata_field.fetched_at()
+            }
+        }
+    }
+}
+
+// every group is a secrets struct of its own, retrieved concurrently from the same source
+fn create_init_for_composed(fields: &[&Ident], group_struct_names: &[Ident], composed_struct_name: &Ident, settings: &Settings) -> TokenStream {
+    let metadata_field = get_metadata_field();
+    let default_version = get_default_version(settings);
+
+    let backend_block = if cfg!(feature = "rt-tokio") {
+        let secrets_client = create_secrets_client(settings);
+        // one client (and one Lambda extension probe) for all secrets
+        let retrieve_from_backend = on_runtime(quote! {
+            let origin = ::secrets_manager_macro_runtime::Origin::configured(Self::client().await, #default_version);
+            Self::retrieve(origin).await
+        });
+
+        quote! {
+            pub async fn new() -> Self {
+                Self::try_new().await.unwrap_or_else(|e| panic!("Could not retrieve secret: {}", e))
+            }
+
+            /// Like `new`, but returns an error instead of panicking when one of the secrets cannot be retrieved
+            pub async fn try_new() -> Result<Self, ::secrets_manager_macro_runtime::Error> {
+                #retrieve_from_backend
+            }
+
+            #secrets_client
+        }
+    } else {
+        quote!()
+    };
+
+    quote! {
+        impl #composed_struct_name {
+            #backend_block
+
+            /// Like `new`, but retrieves the secrets from the given source instead of the configured backend
+            pub async fn new_from_source(source: impl ::secrets_manager_macro_runtime::SecretSource + 'static) -> Self {
+                Self::try_new_from_source(source).await.unwrap_or_else(|e| panic!("Could not retrieve secret: {}", e))
+            }
+
+            pub async fn try_new_from_source(source: impl ::secrets_manager_macro_runtime::SecretSource + 'static) -> Result<Self, ::secrets_manager_macro_runtime::Error> {
+                Self::retrieve(::secrets_manager_macro_runtime::Origin::new(std::sync::Arc::new(source), #default_version)).await
+            }
+
+            async fn retrieve(origin: ::secrets_manager_macro_runtime::Origin) -> Result<Self, ::secrets_manager_macro_runtime::Error> {
+                let (#(#fields,)*) = ::secrets_manager_macro_runtime::futures::try_join!(#(#group_struct_names::retrieve(origin.clone())),*)?;
+
+                Ok(#composed_struct_name {
+                    #(#fields,)*
+                    #metadata_field: ::secrets_manager_macro_runtime::Metadata::new(None, None),
+                })
+            }
+
+            /// Retrieves all secrets again, replacing all values
+            pub async fn refresh(&mut self) {
+                self.try_refresh().await.unwrap_or_else(|e| panic!("Could not retrieve secret: {}", e))
+            }
+
+            /// Like `refresh`, but returns an error instead of panicking, keeping all current values when one of the secrets fails
+            pub async fn try_refresh(&mut self) -> Result<(), ::secrets_manager_macro_runtime::Error> {
+                let (#(#fields,)*) = ::secrets_manager_macro_runtime::futures::try_join!(#(self.#fields.refreshed()),*)?;
+                #(self.#fields = #fields;)*
+                self.#metadata_field = ::secrets_manager_macro_runtime::Metadata::new(None, None);
+                Ok(())
+            }
+
+            /// The moment the current values were retrieved
+            pub fn fetched_at(&self) -> std::time::SystemTime {
+                self.#metadata_field.fetched_at()
             }
         }
     }
 }
 
-pub fn create_output(item: &ItemStruct, keys: &[Ident], actual_base_secret_name: &str, env_setting: &EnvSetting) -> TokenStream {
+// these block or spawn, next to the constructors for the configured backend that also need tokio
+fn create_tokio_helpers(secret_struct_name: &Ident, settings: &Settings) -> TokenStream {
+    let blocking_block = create_blocking_for_secrets(secret_struct_name);
+    let singleton_block = create_singleton_for_secrets(secret_struct_name);
+    let watch_block = create_watch_for_secrets(secret_struct_name, settings);
+
+    quote! {
+        #blocking_block
+
+        #singleton_block
+
+        #watch_block
+    }
+}
+
+pub fn create_output(item: &ItemStruct, keys: &[Ident], actual_base_secret_name: &str, settings: &Settings) -> TokenStream {
     let name = &item.ident;
     let attributes = &item.attrs;
 
     let secret_string_name = get_secret_string_name(name);
-    let secret_string_struct = create_secret_string_struct(&secret_string_name);
+    let secret_string_type = quote!(pub type #secret_string_name = ::secrets_manager_macro_runtime::SecretString;);
     let secret_fields = keys.iter().map(|k| quote!(pub #k: #secret_string_name));
-    let new_impl_block = create_init_for_secrets(keys, name, actual_base_secret_name, env_setting);
+    let metadata_field = get_metadata_field();
+    let new_impl_block = create_init_for_secrets(keys, name, actual_base_secret_name, settings);
+    let tokio_blocks = if cfg!(feature = "rt-tokio") {
+        create_tokio_helpers(name, settings)
+    } else {
+        quote!()
+    };
 
     quote!(
-        #secret_string_struct
+        #secret_string_type
 
         #(#attributes)*
         pub struct #name {
             #(#secret_fields,)*
+            #metadata_field: ::secrets_manager_macro_runtime::Metadata,
+        }
+
+        #new_impl_block
+
+        #tokio_blocks
+    )
+}
+
+pub fn create_parameters_output(item: &ItemStruct, keys: &[ParameterKey], parameter_settings: &ParameterSettings) -> TokenStream {
+    let name = &item.ident;
+    let attributes = &item.attrs;
+
+    let secret_string_name = get_secret_string_name(name);
+    let secret_string_type = quote!(pub type #secret_string_name = ::secrets_manager_macro_runtime::SecretString;);
+    let parameter_fields = keys.iter().map(|k| {
+        let field = &k.field;
+        let field_type = get_parameter_field_type(k, &secret_string_name);
+        quote!(pub #field: #field_type)
+    });
+    let metadata_field = get_metadata_field();
+    let new_impl_block = create_init_for_parameters(keys, name, parameter_settings);
+    // parameters have no version to compare, so there is no `watch`
+    let tokio_blocks = if cfg!(feature = "rt-tokio") {
+        let blocking_block = create_blocking_for_secrets(name);
+        let singleton_block = create_singleton_for_secrets(name);
+
+        quote! {
+            #blocking_block
+
+            #singleton_block
+        }
+    } else {
+        quote!()
+    };
+
+    quote!(
+        #secret_string_type
+
+        #(#attributes)*
+        pub struct #name {
+            #(#parameter_fields,)*
+            #metadata_field: ::secrets_manager_macro_runtime::Metadata,
         }
 
         #new_impl_block
+
+        #tokio_blocks
+    )
+}
+
+pub fn create_composed_output(item: &ItemStruct, fields: &[&Ident], group_struct_names: &[Ident], group_outputs: &[TokenStream], settings: &Settings) -> TokenStream {
+    let name = &item.ident;
+    let attributes = &item.attrs;
+
+    let metadata_field = get_metadata_field();
+    let new_impl_block = create_init_for_composed(fields, group_struct_names, name, settings);
+    // the groups have their own version, so there is no `version_id` or `watch`
+    let tokio_blocks = if cfg!(feature = "rt-tokio") {
+        let blocking_block = create_blocking_for_secrets(name);
+        let singleton_block = create_singleton_for_secrets(name);
+
+        quote! {
+            #blocking_block
+
+            #singleton_block
+        }
+    } else {
+        quote!()
+    };
+
+    quote!(
+        #(#group_outputs)*
+
+        #(#attributes)*
+        pub struct #name {
+            #(pub #fields: #group_struct_names,)*
+            #metadata_field: ::secrets_manager_macro_runtime::Metadata,
+        }
+
+        #new_impl_block
+
+        #tokio_blocks
     )
 }
 
@@ -124,6 +758,7 @@ pub fn create_output(item: &ItemStruct, keys: &[Ident], actual_base_secret_name:
 mod tests {
     use proc_macro2::Span;
     use proc_macro2::Ident;
+    use crate::implementation::input::get_settings;
     use super::*;
 
     #[test]
@@ -134,4 +769,43 @@ mod tests {
 
         assert_eq!(actual.to_string(), "ExampleSecretString".to_string());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn should_only_use_secret_string_for_secure_string_parameters() {
+        let example_ident = Ident::new("ExampleSecretString", Span::call_site());
+        let key = |kind| ParameterKey {
+            field: Ident::new("field", Span::call_site()),
+            name: "field".to_string(),
+            kind,
+        };
+
+        assert_eq!(get_parameter_field_type(&key(ParameterKind::String), &example_ident).to_string(), "String");
+        assert_eq!(get_parameter_field_type(&key(ParameterKind::StringList), &example_ident).to_string(), "Vec < String >");
+        assert_eq!(get_parameter_field_type(&key(ParameterKind::SecureString), &example_ident).to_string(), "ExampleSecretString");
+    }
+
+    #[test]
+    fn should_generate_ident_with_handle_suffix() {
+        let example_ident = Ident::new("Example", Span::call_site());
+
+        let actual = get_handle_name(&example_ident);
+
+        assert_eq!(actual.to_string(), "ExampleHandle".to_string());
+    }
+
+    #[test]
+    fn should_generate_composed_struct_with_field_per_group() {
+        let item: ItemStruct = syn::parse_quote!(struct Services {});
+        let db = Ident::new("db", Span::call_site());
+        let stripe = Ident::new("stripe", Span::call_site());
+        let group_struct_names = vec![Ident::new("ServicesDb", Span::call_site()), Ident::new("ServicesStripe", Span::call_site())];
+
+        let settings = get_settings(quote!()).unwrap();
+
+        let actual = create_composed_output(&item, &[&db, &stripe], &group_struct_names, &[], &settings).to_string();
+
+        assert!(actual.contains("pub db : ServicesDb"));
+        assert!(actual.contains("pub stripe : ServicesStripe"));
+        assert!(actual.contains("try_join ! (ServicesDb :: retrieve (origin . clone ()) , ServicesStripe :: retrieve (origin . clone ()))"));
+    }
+}
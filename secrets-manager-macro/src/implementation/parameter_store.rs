@@ -0,0 +1,121 @@
+use aws_sdk_ssm::error::GetParametersByPathError;
+use aws_sdk_ssm::model::{Parameter, ParameterType};
+use aws_sdk_ssm::output::GetParametersByPathOutput;
+use aws_sdk_ssm::types::SdkError;
+use aws_sdk_ssm::Client;
+use tokio_stream::StreamExt;
+
+use crate::implementation::errors::RetrievalError;
+
+/// The type of a parameter, which decides the type of its field
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ParameterKind {
+    String,
+    StringList,
+    SecureString,
+}
+
+impl From<&Parameter> for ParameterKind {
+    fn from(parameter: &Parameter) -> Self {
+        match parameter.r#type() {
+            Some(ParameterType::StringList) => ParameterKind::StringList,
+            Some(ParameterType::SecureString) => ParameterKind::SecureString,
+            // treat unknown types as plain strings
+            _ => ParameterKind::String,
+        }
+    }
+}
+
+pub struct ParameterStoreClient {
+    client: Client
+}
+
+impl ParameterStoreClient {
+    pub async fn new() -> Self {
+        let shared_config = aws_config::from_env().load().await;
+        ParameterStoreClient {
+            client: Client::new(&shared_config),
+        }
+    }
+
+    /// Names and types of all parameters under the given path, with names relative to that path
+    pub async fn get_parameter_names(&self, path: &str) -> Result<Vec<(String, ParameterKind)>, RetrievalError> {
+        let outputs = self.get_parameters_by_path(path).await?;
+        let names = parameter_names(outputs, path);
+
+        if names.is_empty() {
+            Err(RetrievalError::NotFound(format!("Could not find any parameters under path {}", path)))
+        } else {
+            Ok(names)
+        }
+    }
+
+    async fn get_parameters_by_path(&self, path: &str) -> Result<Vec<GetParametersByPathOutput>, SdkError<GetParametersByPathError>> {
+        self.client.get_parameters_by_path()
+            .path(path)
+            .recursive(true)
+            .into_paginator()
+            .send()
+            .collect()
+            .await
+    }
+}
+
+fn parameter_names(outputs: Vec<GetParametersByPathOutput>, path: &str) -> Vec<(String, ParameterKind)> {
+    outputs.iter()
+        .filter_map(|o| o.parameters())
+        .flatten()
+        .filter_map(|p| p.name().map(|name| (name, ParameterKind::from(p))))
+        .map(|(name, kind)| (name.strip_prefix(path).unwrap_or(name).trim_start_matches('/').to_string(), kind))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parameter_names_should_return_names_relative_to_path() {
+        let outputs = vec![
+            GetParametersByPathOutput::builder()
+                .parameters(Parameter::builder().name("/app/dev/firstParameter").build())
+                .build(),
+            GetParametersByPathOutput::builder()
+                .parameters(Parameter::builder().name("/app/dev/nested/secondParameter").build())
+                .build(),
+        ];
+
+        let actual = parameter_names(outputs, "/app/dev");
+
+        assert_eq!(actual, vec![
+            ("firstParameter".to_string(), ParameterKind::String),
+            ("nested/secondParameter".to_string(), ParameterKind::String),
+        ]);
+    }
+
+    #[test]
+    fn parameter_names_should_return_type_of_each_parameter() {
+        let outputs = vec![
+            GetParametersByPathOutput::builder()
+                .parameters(Parameter::builder().name("/app/list").r#type(ParameterType::StringList).build())
+                .parameters(Parameter::builder().name("/app/password").r#type(ParameterType::SecureString).build())
+                .build(),
+        ];
+
+        let actual = parameter_names(outputs, "/app");
+
+        assert_eq!(actual, vec![
+            ("list".to_string(), ParameterKind::StringList),
+            ("password".to_string(), ParameterKind::SecureString),
+        ]);
+    }
+
+    #[test]
+    fn parameter_names_should_be_empty_without_parameters() {
+        let outputs = vec![GetParametersByPathOutput::builder().build()];
+
+        let actual = parameter_names(outputs, "/app/dev");
+
+        assert!(actual.is_empty());
+    }
+}
@@ -5,40 +5,107 @@ use proc_macro2::{Ident, Span};
 
 use crate::implementation::aws::NonEmptySecrets;
 use crate::implementation::errors::RetrievalError;
-use crate::implementation::input::EnvSetting;
+use crate::implementation::input::{Env, EnvSetting};
+use crate::implementation::parameter_store::ParameterKind;
 
 const HYPHEN: char = '-';
 const UNDERSCORE: char = '_';
+pub const NAME_PLACEHOLDER: &str = "{name}";
+pub const ENV_PLACEHOLDER: &str = "{env}";
+
+/// A template for secret names, like `app/{name}/{env}` or `{env}-{name}`
+#[derive(Clone, Debug, PartialEq)]
+pub struct NamePattern(String);
+
+impl NamePattern {
+    pub fn new(pattern: &str) -> Self {
+        NamePattern(pattern.to_string())
+    }
+
+    /// The pattern used without a `pattern` attribute, `/{env}/{name}` or just `{name}`
+    pub fn default_for(env_setting: &EnvSetting) -> Self {
+        match env_setting {
+            EnvSetting::None => NamePattern::new(NAME_PLACEHOLDER),
+            EnvSetting::Env(_) => NamePattern::new(&format!("/{}/{}", ENV_PLACEHOLDER, NAME_PLACEHOLDER)),
+        }
+    }
+
+    /// The full name of a secret in the env with the given prefix
+    pub fn full_name(&self, base_secret_name: &str, prefix: Option<&str>) -> String {
+        self.with_env(prefix).replace(NAME_PLACEHOLDER, base_secret_name)
+    }
+
+    /// The pattern with only the name filled in, so the env can be added at runtime
+    pub fn with_name(&self, base_secret_name: &str) -> String {
+        self.0.replace(NAME_PLACEHOLDER, base_secret_name)
+    }
+
+    /// The base name of a secret, if its full name follows the pattern for the env with the given prefix
+    pub fn base_name(&self, full_secret_name: &str, prefix: Option<&str>) -> Option<String> {
+        let with_env = self.with_env(prefix);
+        let (before, after) = with_env.split_once(NAME_PLACEHOLDER)?;
+        full_secret_name.strip_prefix(before)
+            .and_then(|rest| rest.strip_suffix(after))
+            .filter(|base| !base.is_empty())
+            .map(|base| base.to_string())
+    }
+
+    /// The pattern for Vault, where the env is the mount and the rest of the pattern the path inside of it,
+    /// so `app/{name}/{env}` becomes `/{env}/app/{name}`
+    pub fn with_env_as_mount(&self) -> Self {
+        let (before, after) = self.0.split_once(ENV_PLACEHOLDER).unwrap_or((&self.0, ""));
+        // drops the separator next to the env, like the slash in app/{env}/{name}
+        let (before, after) = match after.strip_prefix(|c: char| !c.is_alphanumeric() && c != '{') {
+            Some(after) => (before, after),
+            None => (before.strip_suffix(|c: char| !c.is_alphanumeric() && c != '}').unwrap_or(before), after),
+        };
+        let path = format!("{}{}", before, after);
+        NamePattern::new(&format!("/{}/{}", ENV_PLACEHOLDER, path.trim_start_matches('/')))
+    }
+
+    fn with_env(&self, prefix: Option<&str>) -> String {
+        match prefix {
+            Some(prefix) => self.0.replace(ENV_PLACEHOLDER, prefix),
+            None => self.0.clone(),
+        }
+    }
+}
 
 pub struct ValidatedSecrets {
     secrets: Vec<String>,
     env_setting: EnvSetting,
+    pattern: Option<NamePattern>,
 }
 
 impl ValidatedSecrets {
-    pub fn new(found_secret_names: NonEmptySecrets, env_setting: EnvSetting) -> Result<Self, RetrievalError> {
+    pub fn new(found_secret_names: NonEmptySecrets, env_setting: EnvSetting, pattern: Option<NamePattern>) -> Result<Self, RetrievalError> {
         match &env_setting {
             EnvSetting::None if found_secret_names.0.len() != 1 => Err(RetrievalError::DuplicateSecrets(format!("expected to find an exact match, instead found multiple possible secrets: {}. Please specify an exact name", found_secret_names.0.join(",")))),
             EnvSetting::None => Ok(ValidatedSecrets {
                 secrets: found_secret_names.0,
                 env_setting,
+                pattern,
             }),
             EnvSetting::Env(envs) => {
-                let matched: Vec<String> = found_secret_names.0.clone().into_iter().filter(|s| envs.iter().any(|e| s.contains(e))).collect();
+                let matched: Vec<String> = found_secret_names.0.clone().into_iter().filter(|s| envs.iter().any(|e| is_in_env(s, e, pattern.as_ref()))).collect();
 
                 match matched.len().cmp(&envs.len()) {
                     Ordering::Equal => Ok(ValidatedSecrets {
                         secrets: matched,
                         env_setting,
+                        pattern,
                     }),
-                    Ordering::Less => Err(RetrievalError::MissingEnv(format!("received envs {} but only matched these secrets: {}", envs.join(","), matched.join(",")))),
+                    Ordering::Less => Err(RetrievalError::MissingEnv(format!("received envs {} but only matched these secrets: {}", prefixes(envs).join(","), matched.join(",")))),
                     Ordering::Greater => Err(RetrievalError::DuplicateSecrets(format!("expected to find {} secrets, but found more: {}. Please specify an exact name", envs.len(), found_secret_names.0.join(",")))),
                 }
             }
         }
     }
 
-    // would be nice to also support suffix (secret-something/dev)? but would also need to *know* where this is for generating the right 'get' call in output
+    pub fn full_secret_names(&self) -> &[String] {
+        &self.secrets
+    }
+
     pub fn get_full_and_base_secret(&self) -> (String, String) {
         match &self.env_setting {
             EnvSetting::None => {
@@ -46,19 +113,28 @@ impl ValidatedSecrets {
                 let full = self.secrets.first()
                     .unwrap_or_else(|| self.secrets.first().expect("Found secrets to contain at least one secret"))
                     .to_string();
-                // there is no prefix, so base and full are identical
-                (full.clone(), full)
+                // there is no prefix, so without a pattern base and full are identical
+                let base = self.pattern.as_ref()
+                    .and_then(|p| p.base_name(&full, None))
+                    .unwrap_or_else(|| full.clone());
+                (full, base)
             }
             EnvSetting::Env(envs) => {
                 // TODO this assumes that you passed in a dev env. Perhaps better to check all secrets?
                 //  alternatively, pick one secret and assume they all have the same fields
-                let full = self.secrets.iter()
-                    .find(|s| s.contains("/dev/"))
+                let full = envs.iter()
+                    .find(|e| e.name == "dev")
+                    .and_then(|dev| self.secrets.iter().find(|s| is_in_env(s, dev, self.pattern.as_ref())))
                     .unwrap_or_else(|| self.secrets.first().expect("Found secrets to contain at least one secret"))
                     .to_string();
-                let base = envs.iter().fold(full.clone(), |acc, curr| {
-                    acc.replace(&format!("/{curr}/"), "")
-                });
+                let base = match &self.pattern {
+                    Some(pattern) => envs.iter()
+                        .find_map(|e| pattern.base_name(&full, Some(&e.prefix)))
+                        .unwrap_or_else(|| full.clone()),
+                    None => envs.iter().fold(full.clone(), |acc, curr| {
+                        acc.replace(&format!("/{}/", curr.prefix), "")
+                    }),
+                };
 
                 (full, base)
             }
@@ -66,6 +142,18 @@ impl ValidatedSecrets {
     }
 }
 
+// without a pattern, any secret containing the prefix as a 'directory' belongs to the env
+fn is_in_env(secret_name: &str, env: &Env, pattern: Option<&NamePattern>) -> bool {
+    match pattern {
+        Some(pattern) => pattern.base_name(secret_name, Some(&env.prefix)).is_some(),
+        None => secret_name.contains(&format!("/{}/", env.prefix)),
+    }
+}
+
+fn prefixes(envs: &[Env]) -> Vec<&str> {
+    envs.iter().map(|e| e.prefix.as_str()).collect()
+}
+
 pub fn possible_base_names(secret_struct_name: &str) -> Vec<String> {
     let with_hyphen = lowercase_and_add(secret_struct_name, |mut acc| {
         acc.push(HYPHEN);
@@ -108,16 +196,91 @@ pub fn keys_as_ident_list(key_map: HashMap<String, String>) -> Vec<Ident> {
         .collect()
 }
 
+/// The struct generated for a field of a composed struct, like `ServicesDbCredentials` for field `db_credentials` of `Services`
+pub fn group_struct_name(secret_struct_name: &str, field: &str) -> String {
+    field.split(UNDERSCORE).fold(secret_struct_name.to_string(), |mut acc, part| {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            acc.push_str(&first.to_uppercase().to_string());
+            acc.push_str(chars.as_str());
+        }
+        acc
+    })
+}
+
+/// The full names of all secrets we look for, for backends that cannot list every secret
+pub fn possible_full_names(base_secret_names: &[String], env_setting: &EnvSetting, pattern: Option<&NamePattern>) -> Vec<String> {
+    let pattern = pattern.cloned().unwrap_or_else(|| NamePattern::default_for(env_setting));
+    match env_setting {
+        EnvSetting::None => base_secret_names.iter().map(|b| pattern.full_name(b, None)).collect(),
+        EnvSetting::Env(envs) => base_secret_names.iter()
+            .flat_map(|b| envs.iter().map(|e| pattern.full_name(b, Some(&e.prefix))).collect::<Vec<String>>())
+            .collect(),
+    }
+}
+
+/// Paths to look for parameters, one per env. The first one is used for the keys (dev if present, just like for secrets)
+pub fn parameter_paths(path: &str, env_setting: &EnvSetting) -> Vec<String> {
+    match env_setting {
+        EnvSetting::None => vec![path.to_string()],
+        EnvSetting::Env(envs) => {
+            let mut envs: Vec<&Env> = envs.iter().collect();
+            envs.sort_by_key(|e| e.name != "dev");
+            envs.iter().map(|e| format!("{}/{}", path, e.prefix)).collect()
+        }
+    }
+}
+
+/// A field of a parameters struct, with the name and type of the parameter it is filled with
+pub struct ParameterKey {
+    pub field: Ident,
+    pub name: String,
+    pub kind: ParameterKind,
+}
+
+/// Errors (at the given span) for parameters that do not become a valid field, or the same field as another parameter
+pub fn parameter_names_as_key_list(names: Vec<(String, ParameterKind)>, span: Span) -> syn::Result<Vec<ParameterKey>> {
+    let mut names_by_field: HashMap<String, String> = HashMap::new();
+
+    names.into_iter()
+        .map(|(name, kind)| {
+            let field: String = name.chars()
+                .map(|c| if c.is_alphanumeric() { c } else { UNDERSCORE })
+                .collect();
+            // rejects keywords and names starting with a digit, which Ident::new would accept or panic on
+            if syn::parse_str::<Ident>(&field).is_err() {
+                return Err(syn::Error::new(span, format!("parameter {} cannot be used as field {}, please rename it", name, field)));
+            }
+            if let Some(other_name) = names_by_field.insert(field.clone(), name.clone()) {
+                return Err(syn::Error::new(span, format!("parameters {} and {} would both become field {}, please rename one of them", other_name, name, field)));
+            }
+            Ok(ParameterKey {
+                field: Ident::new(&field, Span::call_site()),
+                name,
+                kind,
+            })
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn with_env_as_mount_should_move_env_to_front() {
+        assert_eq!(NamePattern::new("app/{name}/{env}").with_env_as_mount(), NamePattern::new("/{env}/app/{name}"));
+        assert_eq!(NamePattern::new("app/{env}/{name}").with_env_as_mount(), NamePattern::new("/{env}/app/{name}"));
+        assert_eq!(NamePattern::new("{env}-{name}").with_env_as_mount(), NamePattern::new("/{env}/{name}"));
+        assert_eq!(NamePattern::new("/{env}/{name}").with_env_as_mount(), NamePattern::new("/{env}/{name}"));
+    }
+
     #[test]
     fn validate_should_work_when_no_envs_are_present_and_one_secret() {
         let found_secrets = NonEmptySecrets(vec!["sample-secret".to_string()]);
         let env = EnvSetting::None;
 
-        let actual = ValidatedSecrets::new(found_secrets, env);
+        let actual = ValidatedSecrets::new(found_secrets, env, None);
 
         assert!(actual.is_ok());
         assert_eq!(actual.unwrap().secrets.len(), 1);
@@ -128,7 +291,7 @@ mod tests {
         let found_secrets = NonEmptySecrets(vec!["sample-secret".to_string(), "fake-secret".to_string()]);
         let env = EnvSetting::None;
 
-        let actual = ValidatedSecrets::new(found_secrets, env);
+        let actual = ValidatedSecrets::new(found_secrets, env, None);
 
         assert!(actual.is_err());
     }
@@ -136,9 +299,9 @@ mod tests {
     #[test]
     fn validate_should_work_when_all_envs_are_present_filtering_out_unknowns() {
         let found_secrets = NonEmptySecrets(vec!["/prod/sample-secret".to_string(), "/dev/sample-secret".to_string(), "/fake/sample-secret".to_string()]);
-        let envs = EnvSetting::Env(vec!["dev".to_string(), "prod".to_string()]);
+        let envs = EnvSetting::Env(vec![Env::new("dev", "dev"), Env::new("prod", "prod")]);
 
-        let actual = ValidatedSecrets::new(found_secrets, envs);
+        let actual = ValidatedSecrets::new(found_secrets, envs, None);
 
         assert!(actual.is_ok());
         assert_eq!(actual.unwrap().secrets.len(), 2);
@@ -147,9 +310,9 @@ mod tests {
     #[test]
     fn validate_should_fail_when_too_many_envs_are_present() {
         let found_secrets = NonEmptySecrets(vec!["/prod/sample-secret".to_string(), "/dev/sample-secret".to_string(), "/prod/SampleSecret".to_string(), "/fake/sample-secret".to_string()]);
-        let envs = EnvSetting::Env(vec!["dev".to_string(), "prod".to_string()]);
+        let envs = EnvSetting::Env(vec![Env::new("dev", "dev"), Env::new("prod", "prod")]);
 
-        let actual = ValidatedSecrets::new(found_secrets, envs);
+        let actual = ValidatedSecrets::new(found_secrets, envs, None);
 
         assert!(actual.is_err());
     }
@@ -157,9 +320,9 @@ mod tests {
     #[test]
     fn validate_should_fail_when_not_all_envs_are_present() {
         let found_secrets = NonEmptySecrets(vec!["/prod/sample-secret".to_string()]);
-        let env = EnvSetting::Env(vec!["dev".to_string(), "prod".to_string()]);
+        let env = EnvSetting::Env(vec![Env::new("dev", "dev"), Env::new("prod", "prod")]);
 
-        let actual = ValidatedSecrets::new(found_secrets, env);
+        let actual = ValidatedSecrets::new(found_secrets, env, None);
 
         assert!(actual.is_err());
     }
@@ -169,7 +332,7 @@ mod tests {
         let found_secrets = NonEmptySecrets(vec!["sample-secret".to_string(), "OtherSecret".to_string()]);
         let env = EnvSetting::None;
 
-        let actual = ValidatedSecrets::new(found_secrets, env);
+        let actual = ValidatedSecrets::new(found_secrets, env, None);
 
         assert!(actual.is_err());
     }
@@ -179,7 +342,7 @@ mod tests {
         let found_secrets = NonEmptySecrets(vec!["sample-secret".to_string()]);
         let env = EnvSetting::None;
 
-        let actual = ValidatedSecrets::new(found_secrets, env).unwrap();
+        let actual = ValidatedSecrets::new(found_secrets, env, None).unwrap();
         let (actual_full, actual_base) = actual.get_full_and_base_secret();
 
         assert_eq!(actual_full, "sample-secret");
@@ -189,21 +352,43 @@ mod tests {
     #[test]
     fn get_full_and_base_secret_should_get_an_env_when_dev_is_not_available() {
         let found_secrets = NonEmptySecrets(vec!["/prod/sample-secret".to_string(), "/acc/sample-secret".to_string()]);
-        let env = EnvSetting::Env(vec!["prod".to_string(), "acc".to_string()]);
+        let env = EnvSetting::Env(vec![Env::new("prod", "prod"), Env::new("acc", "acc")]);
 
-        let actual = ValidatedSecrets::new(found_secrets, env).unwrap();
+        let actual = ValidatedSecrets::new(found_secrets, env, None).unwrap();
         let (actual_full, actual_base) = actual.get_full_and_base_secret();
 
         assert_eq!(actual_full, "/prod/sample-secret");
         assert_eq!(actual_base, "sample-secret");
     }
 
+    #[test]
+    fn full_secret_names_should_contain_secret_of_every_env() {
+        let found_secrets = NonEmptySecrets(vec!["/prod/sample-secret".to_string(), "/dev/sample-secret".to_string()]);
+        let env = EnvSetting::Env(vec![Env::new("prod", "prod"), Env::new("dev", "dev")]);
+
+        let actual = ValidatedSecrets::new(found_secrets, env, None).unwrap();
+
+        assert_eq!(actual.full_secret_names(), &["/prod/sample-secret".to_string(), "/dev/sample-secret".to_string()]);
+    }
+
+    #[test]
+    fn get_full_and_base_secret_should_use_prefix_of_dev_env_and_strip_it_from_base() {
+        let found_secrets = NonEmptySecrets(vec!["/production/sample-secret".to_string(), "/development/sample-secret".to_string()]);
+        let env = EnvSetting::Env(vec![Env::new("prod", "production"), Env::new("dev", "development")]);
+
+        let actual = ValidatedSecrets::new(found_secrets, env, None).unwrap();
+        let (actual_full, actual_base) = actual.get_full_and_base_secret();
+
+        assert_eq!(actual_full, "/development/sample-secret");
+        assert_eq!(actual_base, "sample-secret");
+    }
+
     #[test]
     fn get_full_and_base_secret_should_by_fallback_to_first_secret() {
         let found_secrets = NonEmptySecrets(vec!["sample-secret".to_string()]);
         let env = EnvSetting::None;
 
-        let actual = ValidatedSecrets::new(found_secrets, env).unwrap();
+        let actual = ValidatedSecrets::new(found_secrets, env, None).unwrap();
         let (actual_full, actual_base) = actual.get_full_and_base_secret();
 
         assert_eq!(actual_full, "sample-secret");
@@ -260,4 +445,113 @@ mod tests {
         assert!(as_strings.contains(&"firstKey".to_string()));
         assert!(as_strings.contains(&"secondKey".to_string()));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn possible_full_names_should_return_base_names_when_no_envs() {
+        let actual = possible_full_names(&["SampleSecret".to_string()], &EnvSetting::None, None);
+
+        assert_eq!(actual, vec!["SampleSecret"]);
+    }
+
+    #[test]
+    fn possible_full_names_should_prefix_base_names_with_env_prefixes() {
+        let env_setting = EnvSetting::Env(vec![Env::new("dev", "development"), Env::new("prod", "prod")]);
+
+        let actual = possible_full_names(&["sample-secret".to_string()], &env_setting, None);
+
+        assert_eq!(actual, vec!["/development/sample-secret", "/prod/sample-secret"]);
+    }
+
+    #[test]
+    fn possible_full_names_should_follow_pattern() {
+        let env_setting = EnvSetting::Env(vec![Env::new("dev", "dev"), Env::new("prod", "prod")]);
+        let pattern = NamePattern::new("app/{name}/{env}");
+
+        let actual = possible_full_names(&["sample-secret".to_string()], &env_setting, Some(&pattern));
+
+        assert_eq!(actual, vec!["app/sample-secret/dev", "app/sample-secret/prod"]);
+    }
+
+    #[test]
+    fn base_name_should_strip_pattern_for_env() {
+        let pattern = NamePattern::new("{env}-{name}");
+
+        assert_eq!(pattern.base_name("dev-sample-secret", Some("dev")), Some("sample-secret".to_string()));
+        assert_eq!(pattern.base_name("prod-sample-secret", Some("dev")), None);
+    }
+
+    #[test]
+    fn validate_should_match_envs_with_pattern() {
+        let found_secrets = NonEmptySecrets(vec!["app/sample-secret/prod".to_string(), "app/sample-secret/dev".to_string()]);
+        let env = EnvSetting::Env(vec![Env::new("dev", "dev"), Env::new("prod", "prod")]);
+
+        let actual = ValidatedSecrets::new(found_secrets, env, Some(NamePattern::new("app/{name}/{env}"))).unwrap();
+        let (actual_full, actual_base) = actual.get_full_and_base_secret();
+
+        assert_eq!(actual_full, "app/sample-secret/dev");
+        assert_eq!(actual_base, "sample-secret");
+    }
+
+    #[test]
+    fn get_full_and_base_secret_should_strip_pattern_when_no_envs() {
+        let found_secrets = NonEmptySecrets(vec!["app/sample-secret".to_string()]);
+
+        let actual = ValidatedSecrets::new(found_secrets, EnvSetting::None, Some(NamePattern::new("app/{name}"))).unwrap();
+        let (actual_full, actual_base) = actual.get_full_and_base_secret();
+
+        assert_eq!(actual_full, "app/sample-secret");
+        assert_eq!(actual_base, "sample-secret");
+    }
+
+    #[test]
+    fn parameter_paths_should_return_path_when_no_envs() {
+        let actual = parameter_paths("/app", &EnvSetting::None);
+
+        assert_eq!(actual, vec!["/app"]);
+    }
+
+    #[test]
+    fn parameter_paths_should_return_path_per_env_prefix_starting_with_dev() {
+        let env_setting = EnvSetting::Env(vec![Env::new("prod", "prod"), Env::new("dev", "development")]);
+
+        let actual = parameter_paths("/app", &env_setting);
+
+        assert_eq!(actual, vec!["/app/development", "/app/prod"]);
+    }
+
+    #[test]
+    fn parameter_names_as_key_list_should_replace_characters_that_are_invalid_in_field_names() {
+        let actual = parameter_names_as_key_list(vec![("nested/some-parameter".to_string(), ParameterKind::SecureString)], Span::call_site()).unwrap();
+
+        assert_eq!(actual[0].field.to_string(), "nested_some_parameter");
+        assert_eq!(actual[0].name, "nested/some-parameter");
+        assert_eq!(actual[0].kind, ParameterKind::SecureString);
+    }
+
+    #[test]
+    fn parameter_names_as_key_list_should_fail_for_name_starting_with_digit() {
+        let actual = parameter_names_as_key_list(vec![("1password".to_string(), ParameterKind::String)], Span::call_site());
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn parameter_names_as_key_list_should_fail_for_keyword() {
+        let actual = parameter_names_as_key_list(vec![("type".to_string(), ParameterKind::String)], Span::call_site());
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn parameter_names_as_key_list_should_fail_when_names_become_same_field() {
+        let actual = parameter_names_as_key_list(vec![("a-b".to_string(), ParameterKind::String), ("a_b".to_string(), ParameterKind::String)], Span::call_site());
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn group_struct_name_should_append_field_in_camel_case() {
+        assert_eq!(group_struct_name("Services", "db"), "ServicesDb");
+        assert_eq!(group_struct_name("Services", "db_credentials"), "ServicesDbCredentials");
+    }
+}
@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+
+use hyper::body::Bytes;
+use hyper::{Body, Client, Request, StatusCode};
+use hyper_rustls::HttpsConnectorBuilder;
+
+use crate::implementation::aws::NonEmptySecrets;
+use crate::implementation::errors::RetrievalError;
+use crate::implementation::input::EnvSetting;
+use crate::implementation::transformations::{self, NamePattern};
+
+const DEFAULT_MOUNT: &str = "secret";
+
+/// Secrets in a HashiCorp Vault KV v2 engine, where env prefixes are mounts.
+/// The runtime crate depends on this one and cannot share its Vault client, so this is a minimal one for the compile time check,
+/// building the same URIs and reading the same responses
+pub struct VaultClient {
+    address: String,
+    token: String,
+    default_mount: String,
+}
+
+impl VaultClient {
+    pub fn from_env() -> Result<Self, RetrievalError> {
+        match (std::env::var("VAULT_ADDR"), std::env::var("VAULT_TOKEN")) {
+            (Ok(address), Ok(token)) => Ok(VaultClient {
+                address: address.trim_end_matches('/').to_string(),
+                token,
+                default_mount: std::env::var("VAULT_MOUNT").unwrap_or_else(|_| DEFAULT_MOUNT.to_string()),
+            }),
+            _ => Err(RetrievalError::MissingEnv("the vault backend needs VAULT_ADDR and VAULT_TOKEN during compilation".to_string())),
+        }
+    }
+
+    pub async fn get_filtered_secret_list(&self, base_secret_names: Vec<String>, env_setting: &EnvSetting, pattern: Option<&NamePattern>) -> Result<NonEmptySecrets, RetrievalError> {
+        let mut possible_secrets = vec![];
+
+        for name in transformations::possible_full_names(&base_secret_names, env_setting, pattern) {
+            let (status, _) = self.get(&self.uri(&name, "metadata")).await?;
+            match status {
+                StatusCode::OK => possible_secrets.push(name),
+                StatusCode::NOT_FOUND => {}
+                _ => return Err(RetrievalError::Vault(format!("could not check secret {} in Vault (status {}) - is your VAULT_TOKEN valid?", name, status))),
+            }
+        }
+
+        if possible_secrets.is_empty() {
+            Err(RetrievalError::NotFound(format!(
+                "Could not find secret in Vault with any of these names: {}",
+                base_secret_names.join(",")
+            )))
+        } else {
+            Ok(NonEmptySecrets(possible_secrets))
+        }
+    }
+
+    pub async fn get_secret_as_map(&self, full_secret_name: &str) -> Result<HashMap<String, String>, RetrievalError> {
+        let (status, body) = self.get(&self.uri(full_secret_name, "data")).await?;
+
+        if status != StatusCode::OK {
+            return Err(RetrievalError::Vault(format!("could not get secret {} from Vault (status {})", full_secret_name, status)));
+        }
+        values_from_response(&body)
+    }
+
+    // a secret named /dev/some-secret lives in mount dev, one without an env in the default mount
+    fn uri(&self, secret_name: &str, kind: &str) -> String {
+        let (mount, path) = match secret_name.strip_prefix('/').and_then(|name| name.split_once('/')) {
+            Some((mount, path)) => (mount, path),
+            None => (self.default_mount.as_str(), secret_name.trim_start_matches('/')),
+        };
+        format!("{}/v1/{}/{}/{}", self.address, encode_path(mount), kind, encode_path(path))
+    }
+
+    async fn get(&self, uri: &str) -> Result<(StatusCode, Bytes), RetrievalError> {
+        let connector = HttpsConnectorBuilder::new()
+            .with_native_roots()
+            .https_or_http()
+            .enable_http1()
+            .build();
+        let request = Request::get(uri)
+            .header("X-Vault-Token", &self.token)
+            .body(Body::empty())
+            .map_err(|e| RetrievalError::Vault(format!("could not create request for Vault: {}", e)))?;
+
+        let response = Client::builder()
+            .build::<_, Body>(connector)
+            .request(request)
+            .await
+            .map_err(|e| RetrievalError::Vault(format!("could not reach Vault at {}: {}", self.address, e)))?;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .map_err(|e| RetrievalError::Vault(format!("could not read response from Vault: {}", e)))?;
+        Ok((status, body))
+    }
+}
+
+// percent-encodes every segment, keeping the slashes between them, as the runtime client does
+fn encode_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| segment.bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+                _ => format!("%{:02X}", b),
+            })
+            .collect::<String>())
+        .collect::<Vec<String>>()
+        .join("/")
+}
+
+// KV v2 wraps the values in a second `data`, only the keys matter here
+fn values_from_response(body: &[u8]) -> Result<HashMap<String, String>, RetrievalError> {
+    let output: serde_json::Value = serde_json::from_slice(body)?;
+    let values = output["data"]["data"]
+        .as_object()
+        .map(|values| values.iter()
+            .map(|(key, value)| match value {
+                serde_json::Value::String(v) => (key.to_string(), v.to_string()),
+                other => (key.to_string(), other.to_string()),
+            })
+            .collect())
+        .unwrap_or_default();
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::{SocketAddr, TcpListener};
+    use std::thread::JoinHandle;
+
+    use crate::implementation::input::Env;
+
+    use super::*;
+
+    fn client() -> VaultClient {
+        client_at("http://127.0.0.1:8200")
+    }
+
+    fn client_at(address: &str) -> VaultClient {
+        VaultClient {
+            address: address.to_string(),
+            token: "a-token".to_string(),
+            default_mount: DEFAULT_MOUNT.to_string(),
+        }
+    }
+
+    // answers one request per response, in order, and returns the requests it received
+    fn stand_in(responses: Vec<(&'static str, &'static str)>) -> (SocketAddr, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let handle = std::thread::spawn(move || {
+            responses.into_iter()
+                .map(|(status, body)| {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let mut request = Vec::new();
+                    let mut buffer = [0; 1024];
+                    while !request.ends_with(b"\r\n\r\n") {
+                        let read = stream.read(&mut buffer).unwrap();
+                        request.extend_from_slice(&buffer[..read]);
+                    }
+                    let response = format!("HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", status, body.len(), body);
+                    stream.write_all(response.as_bytes()).unwrap();
+                    String::from_utf8(request).unwrap()
+                })
+                .collect()
+        });
+        (address, handle)
+    }
+
+    #[test]
+    fn uri_should_use_env_prefix_as_mount() {
+        let actual = client().uri("/dev/sample-secret", "metadata");
+
+        assert_eq!(actual, "http://127.0.0.1:8200/v1/dev/metadata/sample-secret");
+    }
+
+    #[test]
+    fn uri_should_use_default_mount_without_env() {
+        let actual = client().uri("SampleSecret", "data");
+
+        assert_eq!(actual, "http://127.0.0.1:8200/v1/secret/data/SampleSecret");
+    }
+
+    #[test]
+    fn uri_should_encode_mount_and_path() {
+        let actual = client().uri("/dev space/team/sample secret#1", "data");
+
+        assert_eq!(actual, "http://127.0.0.1:8200/v1/dev%20space/data/team/sample%20secret%231");
+    }
+
+    #[test]
+    fn values_from_response_should_return_keys_of_nested_data() {
+        let body = r#"{ "data": { "data": { "key1": "value1", "key2": 2 }, "metadata": { "version": 1 } } }"#;
+
+        let actual = values_from_response(body.as_bytes()).unwrap();
+
+        assert_eq!(actual.get("key1").unwrap(), "value1");
+        assert_eq!(actual.get("key2").unwrap(), "2");
+    }
+
+    #[tokio::test]
+    async fn get_filtered_secret_list_should_keep_secrets_that_exist() {
+        let (address, handle) = stand_in(vec![("200 OK", "{}"), ("404 Not Found", r#"{ "errors": [] }"#)]);
+        let env_setting = EnvSetting::Env(vec![Env::new("dev", "dev"), Env::new("prod", "prod")]);
+
+        let actual = client_at(&format!("http://{}", address)).get_filtered_secret_list(vec!["sample-secret".to_string()], &env_setting, None).await.unwrap();
+        let requests = handle.join().unwrap();
+
+        assert_eq!(actual.0, vec!["/dev/sample-secret".to_string()]);
+        assert!(requests[0].starts_with("GET /v1/dev/metadata/sample-secret HTTP/1.1"));
+        assert!(requests[0].to_lowercase().contains("x-vault-token: a-token"));
+        assert!(requests[1].starts_with("GET /v1/prod/metadata/sample-secret HTTP/1.1"));
+    }
+
+    #[tokio::test]
+    async fn get_filtered_secret_list_should_fail_for_rejected_token() {
+        let (address, handle) = stand_in(vec![("403 Forbidden", r#"{ "errors": ["permission denied"] }"#)]);
+
+        let actual = client_at(&format!("http://{}", address)).get_filtered_secret_list(vec!["SampleSecret".to_string()], &EnvSetting::None, None).await;
+        handle.join().unwrap();
+
+        assert!(matches!(actual, Err(RetrievalError::Vault(_))));
+    }
+
+    #[tokio::test]
+    async fn get_secret_as_map_should_return_values_of_secret() {
+        let body = r#"{ "data": { "data": { "key1": "value1", "key2": 2 }, "metadata": { "version": 1 } } }"#;
+        let (address, handle) = stand_in(vec![("200 OK", body)]);
+
+        let actual = client_at(&format!("http://{}", address)).get_secret_as_map("SampleSecret").await.unwrap();
+        let requests = handle.join().unwrap();
+
+        assert_eq!(actual.get("key1").unwrap(), "value1");
+        assert_eq!(actual.get("key2").unwrap(), "2");
+        assert!(requests[0].starts_with("GET /v1/secret/data/SampleSecret HTTP/1.1"));
+    }
+
+    #[tokio::test]
+    async fn get_secret_as_map_should_fail_when_vault_is_unreachable() {
+        let actual = client_at("http://127.0.0.1:1").get_secret_as_map("SampleSecret").await;
+
+        assert!(matches!(actual, Err(RetrievalError::Vault(_))));
+    }
+}
@@ -5,8 +5,38 @@ mod implementation;
 use crate::implementation::entrypoint;
 use proc_macro::{TokenStream};
 
-/// Will fill the given struct with secrets retrieved from AWS Secrets Manager
+/// Will fill the given struct with secrets retrieved from AWS Secrets Manager.
+///
+/// Checks at compile time that the secret exists, and adds a field for every key of its JSON value. Attributes:
+///
+/// - `envs = dev,prod` (or `dev => development`): env specific secrets, named `/{env}/{name}` by default
+/// - `env_var = "APP_STAGE"` and `default_env = "dev"`: where the env is read from at runtime, and the env without one
+/// - `name = "some/secret"`: the exact name of the secret, instead of one derived from the struct name
+/// - `pattern = "app/{name}/{env}"`: another naming convention for env specific secrets
+/// - `version_stage = "AWSPENDING"`: the stage retrieved by `new`
+/// - `retries = 5` and `timeout_ms = 2000`: retries and total timeout of the SDK
+/// - `cache_ttl_secs = 300`: shares retrieved secrets in a process-wide cache
+/// - `backend = "auto"`, `"sdk"`, `"lambda_extension"`, `"vault"` or `"mounted_dir"`: where secrets are retrieved from at runtime
+/// - `env_overrides = true` (or a prefix): lets environment variables override keys
+///
+/// Fields marked with `#[secret(from = "...")]` and type `_` are each filled with a secret of their own.
+/// See the crate documentation for examples and the generated constructors
 #[proc_macro_attribute]
 pub fn build_secrets_struct(attr: TokenStream, item: TokenStream) -> TokenStream {
     entrypoint::create_secrets_manager(attr.into(), item.into()).into()
 }
+
+/// Will fill the given struct with parameters retrieved from AWS Systems Manager Parameter Store.
+///
+/// Checks at compile time which parameters exist under the path, and adds a field for each of them. Attributes:
+///
+/// - `path = "/app"`: the path of the parameters, required
+/// - `envs = dev,prod` (or `dev => development`): env specific parameters, under `{path}/{env}`
+/// - `env_var = "APP_STAGE"` and `default_env = "dev"`: where the env is read from at runtime, and the env without one
+/// - `retries = 5` and `timeout_ms = 2000`: retries and total timeout of the SDK
+///
+/// See the crate documentation for examples
+#[proc_macro_attribute]
+pub fn build_parameters_struct(attr: TokenStream, item: TokenStream) -> TokenStream {
+    entrypoint::create_parameter_store(attr.into(), item.into()).into()
+}
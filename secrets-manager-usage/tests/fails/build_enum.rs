@@ -1,4 +1,4 @@
-use secrets_manager_macro::build_secrets_struct;
+use secrets_manager_macro_runtime::build_secrets_struct;
 
 #[build_secrets_struct]
 pub enum DoesNotWorkForEnum {}
@@ -0,0 +1,9 @@
+use secrets_manager_macro_runtime::build_secrets_struct;
+
+#[build_secrets_struct]
+struct Services {
+    #[secret(from = "NoPrefixSecret")]
+    no_prefix: String,
+}
+
+fn main() {}
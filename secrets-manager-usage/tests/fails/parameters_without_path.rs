@@ -0,0 +1,6 @@
+use secrets_manager_macro_runtime::build_parameters_struct;
+
+#[build_parameters_struct(envs = dev,prod)]
+struct TestParameters {}
+
+fn main() {}
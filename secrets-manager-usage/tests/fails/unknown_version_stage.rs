@@ -0,0 +1,6 @@
+use secrets_manager_macro_runtime::build_secrets_struct;
+
+#[build_secrets_struct(version_stage = "UNKNOWN_STAGE")]
+struct NoPrefixSecret {}
+
+fn main() {}
@@ -1,4 +1,4 @@
-use secrets_manager_macro::build_secrets_struct;
+use secrets_manager_macro_runtime::build_secrets_struct;
 
 #[tokio::test]
 async fn should_retrieve_secrets_with_specified_envs_and_retrieve_correct_values() {
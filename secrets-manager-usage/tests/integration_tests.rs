@@ -1,8 +1,11 @@
+use std::collections::HashMap;
+use std::time::Duration;
 use assert_impl::assert_impl;
-use secrets_manager_macro::build_secrets_struct;
+use secrets_manager_macro_runtime::{async_trait, build_secrets_struct, Error, SecretSource};
 
 #[tokio::test]
 async fn should_retrieve_secrets_with_specified_envs() {
+    // the only test that uses the default ENV variable, the others have one of their own
     std::env::set_var("ENV", "dev");
 
     #[build_secrets_struct(envs = dev,prod)]
@@ -25,3 +28,292 @@ async fn should_retrieve_secrets_no_env_keeping_other_annotations() {
     assert_eq!(secrets.thirdKey.as_ref(), "thirdValue");
     assert_impl!(core::fmt::Debug: NoPrefixSecret);
 }
+
+#[tokio::test]
+async fn should_retrieve_secrets_falling_back_to_default_env() {
+    #[build_secrets_struct(envs = dev,prod, env_var = "SECRETS_MANAGER_UNSET_STAGE", default_env = "dev")]
+    struct SecretsManagerTestSecret {}
+
+    let secrets = SecretsManagerTestSecret::new().await;
+
+    assert_eq!(secrets.firstKey.as_ref(), "firstValue");
+}
+
+#[tokio::test]
+async fn should_refresh_secrets_and_update_fetched_at() {
+    #[build_secrets_struct]
+    struct NoPrefixSecret {}
+
+    let mut secrets = NoPrefixSecret::new().await;
+    let first_fetched_at = secrets.fetched_at();
+
+    secrets.refresh().await;
+
+    assert_eq!(secrets.thirdKey.as_ref(), "thirdValue");
+    assert!(secrets.version_id().is_some());
+    assert!(secrets.fetched_at() >= first_fetched_at);
+}
+
+#[tokio::test]
+async fn should_try_to_retrieve_secrets() {
+    #[build_secrets_struct]
+    struct NoPrefixSecret {}
+
+    let secrets = NoPrefixSecret::try_new().await.unwrap();
+
+    assert_eq!(secrets.thirdKey.as_ref(), "thirdValue");
+}
+
+#[tokio::test]
+async fn should_watch_secrets_with_cloneable_handle() {
+    #[build_secrets_struct]
+    struct NoPrefixSecret {}
+
+    let handle = NoPrefixSecret::watch(Duration::from_secs(60)).await;
+    let cloned_handle = handle.clone();
+
+    assert_eq!(cloned_handle.current().thirdKey.as_ref(), "thirdValue");
+    assert_eq!(handle.subscribe().borrow().thirdKey.as_ref(), "thirdValue");
+}
+
+#[tokio::test]
+async fn should_retrieve_secrets_once_for_global_accessor() {
+    #[build_secrets_struct]
+    struct NoPrefixSecret {}
+
+    let (first, second) = tokio::join!(NoPrefixSecret::get(), NoPrefixSecret::get());
+
+    assert_eq!(first.thirdKey.as_ref(), "thirdValue");
+    assert!(std::ptr::eq(first, second));
+}
+
+#[test]
+fn should_retrieve_secrets_blocking() {
+    #[build_secrets_struct]
+    struct NoPrefixSecret {}
+
+    let secrets = NoPrefixSecret::new_blocking();
+
+    assert_eq!(secrets.thirdKey.as_ref(), "thirdValue");
+}
+
+#[tokio::test]
+async fn should_retrieve_secrets_blocking_from_within_a_runtime() {
+    #[build_secrets_struct]
+    struct NoPrefixSecret {}
+
+    let secrets = NoPrefixSecret::try_new_blocking().unwrap();
+
+    assert_eq!(secrets.thirdKey.as_ref(), "thirdValue");
+}
+
+#[tokio::test]
+async fn should_retrieve_secrets_at_stage_and_version() {
+    #[build_secrets_struct(version_stage = "AWSCURRENT")]
+    struct NoPrefixSecret {}
+
+    let current = NoPrefixSecret::new_at_stage("AWSCURRENT").await;
+    let mut same_version = NoPrefixSecret::new_at_version(current.version_id().unwrap()).await;
+    same_version.refresh().await;
+
+    assert_eq!(same_version.thirdKey.as_ref(), "thirdValue");
+    assert_eq!(current.version_id(), same_version.version_id());
+}
+
+#[tokio::test]
+async fn should_retrieve_current_and_previous_secrets() {
+    #[build_secrets_struct]
+    struct NoPrefixSecret {}
+
+    let secrets = NoPrefixSecret::load_current_and_previous().await;
+
+    let actual = secrets.with_fallback(|s| {
+        let value = s.thirdKey.as_ref().to_string();
+        async move { Ok::<String, String>(value) }
+    }, |_| true).await;
+
+    assert_eq!(actual.unwrap(), "thirdValue");
+}
+
+#[tokio::test]
+async fn should_retrieve_secrets_with_retries_and_timeout() {
+    #[build_secrets_struct(retries = 3, timeout_ms = 5000)]
+    struct NoPrefixSecret {}
+
+    let secrets = NoPrefixSecret::new().await;
+
+    assert_eq!(secrets.thirdKey.as_ref(), "thirdValue");
+}
+
+#[tokio::test]
+async fn should_retrieve_cached_secrets() {
+    #[build_secrets_struct(cache_ttl_secs = 60)]
+    struct NoPrefixSecret {}
+
+    let first = NoPrefixSecret::new().await;
+    let mut second = NoPrefixSecret::new().await;
+    second.refresh().await;
+
+    assert_eq!(first.thirdKey.as_ref(), "thirdValue");
+    assert_eq!(first.version_id(), second.version_id());
+}
+
+#[tokio::test]
+async fn should_retrieve_secrets_with_sdk_backend() {
+    #[build_secrets_struct(backend = "sdk")]
+    struct NoPrefixSecret {}
+
+    let secrets = NoPrefixSecret::new().await;
+
+    assert_eq!(secrets.thirdKey.as_ref(), "thirdValue");
+}
+
+#[tokio::test]
+async fn should_override_keys_with_env_variables() {
+    std::env::set_var("NO_PREFIX_OVERRIDE_THIRDKEY", "overriddenValue");
+
+    #[build_secrets_struct(env_overrides = "NO_PREFIX_OVERRIDE")]
+    struct NoPrefixSecret {}
+
+    let secrets = NoPrefixSecret::new().await;
+
+    assert_eq!(secrets.thirdKey.as_ref(), "overriddenValue");
+}
+
+struct FakeSource;
+
+#[async_trait]
+impl SecretSource for FakeSource {
+    async fn fetch(&self, secret_name: &str) -> Result<HashMap<String, String>, Error> {
+        assert_eq!(secret_name, "NoPrefixSecret");
+        Ok(HashMap::from([("thirdKey".to_string(), "fakeValue".to_string())]))
+    }
+}
+
+#[tokio::test]
+async fn should_retrieve_secrets_from_custom_source() {
+    #[build_secrets_struct]
+    struct NoPrefixSecret {}
+
+    let secrets = NoPrefixSecret::new_from_source(FakeSource).await;
+
+    assert_eq!(secrets.thirdKey.as_ref(), "fakeValue");
+}
+
+struct EmptySource;
+
+#[async_trait]
+impl SecretSource for EmptySource {
+    async fn fetch(&self, _secret_name: &str) -> Result<HashMap<String, String>, Error> {
+        Ok(HashMap::new())
+    }
+}
+
+#[tokio::test]
+async fn should_supply_missing_keys_with_env_variables() {
+    std::env::set_var("MISSING_KEY_OVERRIDE_THIRDKEY", "suppliedValue");
+
+    #[build_secrets_struct(env_overrides = "MISSING_KEY_OVERRIDE")]
+    struct NoPrefixSecret {}
+
+    let secrets = NoPrefixSecret::new_from_source(EmptySource).await;
+
+    assert_eq!(secrets.thirdKey.as_ref(), "suppliedValue");
+}
+
+#[tokio::test]
+async fn should_compare_and_hash_values_only() {
+    #[build_secrets_struct]
+    #[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+    struct NoPrefixSecret {}
+
+    let first = NoPrefixSecret::new_from_source(FakeSource).await;
+    let second = NoPrefixSecret::new_from_source(FakeSource).await;
+
+    assert_eq!(first, second);
+    assert_ne!(first, NoPrefixSecret::default());
+    assert_impl!(core::hash::Hash: NoPrefixSecret);
+}
+
+#[tokio::test]
+async fn should_refresh_from_custom_source() {
+    #[build_secrets_struct]
+    struct NoPrefixSecret {}
+
+    let mut secrets = NoPrefixSecret::new_from_source(FakeSource).await;
+    secrets.try_refresh().await.unwrap();
+
+    assert_eq!(secrets.thirdKey.as_ref(), "fakeValue");
+}
+
+#[tokio::test]
+async fn should_not_share_cache_between_custom_source_and_backend() {
+    #[build_secrets_struct(cache_ttl_secs = 60)]
+    struct NoPrefixSecret {}
+
+    let from_source = NoPrefixSecret::new_from_source(FakeSource).await;
+    let from_backend = NoPrefixSecret::new().await;
+    let from_source_again = NoPrefixSecret::new_from_source(FakeSource).await;
+
+    assert_eq!(from_source.thirdKey.as_ref(), "fakeValue");
+    assert_eq!(from_backend.thirdKey.as_ref(), "thirdValue");
+    assert_eq!(from_source_again.thirdKey.as_ref(), "fakeValue");
+}
+
+#[tokio::test]
+async fn should_retrieve_composed_secrets() {
+    #[build_secrets_struct]
+    struct Services {
+        #[secret(from = "NoPrefixSecret")]
+        no_prefix: _,
+        #[secret(from = "OtherNoPrefixSecret")]
+        other: _,
+    }
+
+    let secrets = Services::new().await;
+
+    assert_eq!(secrets.no_prefix.thirdKey.as_ref(), "thirdValue");
+    assert_eq!(secrets.other.fourthKey.as_ref(), "fourthValue");
+}
+
+#[tokio::test]
+async fn should_override_composed_secrets_with_prefix_of_composed_struct() {
+    std::env::set_var("OVERRIDDENSERVICES_OTHER_FOURTHKEY", "overriddenValue");
+
+    #[build_secrets_struct(env_overrides = true)]
+    struct OverriddenServices {
+        #[secret(from = "NoPrefixSecret")]
+        no_prefix: _,
+        #[secret(from = "OtherNoPrefixSecret")]
+        other: _,
+    }
+
+    let secrets = OverriddenServices::new().await;
+
+    assert_eq!(secrets.no_prefix.thirdKey.as_ref(), "thirdValue");
+    assert_eq!(secrets.other.fourthKey.as_ref(), "overriddenValue");
+}
+
+#[tokio::test]
+async fn should_retrieve_secrets_with_given_name() {
+    std::env::set_var("GIVEN_NAME_ENV", "dev");
+
+    #[build_secrets_struct(name = "secrets-manager-test-secret", envs = dev,prod, env_var = "GIVEN_NAME_ENV")]
+    struct DatabaseCredentials {}
+
+    let secrets = DatabaseCredentials::new().await;
+
+    assert_eq!(secrets.firstKey.as_ref(), "firstValue");
+}
+
+#[tokio::test]
+async fn should_retrieve_secrets_following_pattern() {
+    std::env::set_var("PATTERN_ENV", "dev");
+
+    #[build_secrets_struct(envs = dev,prod, pattern = "secrets-manager-test/{name}/{env}", env_var = "PATTERN_ENV")]
+    struct PatternSecret {}
+
+    let secrets = PatternSecret::new().await;
+
+    assert_eq!(secrets.fifthKey.as_ref(), "fifthValue");
+}
@@ -0,0 +1,34 @@
+use secrets_manager_macro_runtime::build_parameters_struct;
+
+#[tokio::test]
+async fn should_retrieve_parameters_with_specified_envs() {
+    #[build_parameters_struct(path = "/secrets-manager-test-parameters", envs = dev,prod, env_var = "SECRETS_MANAGER_UNSET_STAGE", default_env = "dev")]
+    struct TestParameters {}
+
+    let parameters = TestParameters::new().await;
+
+    assert_eq!(parameters.firstParameter, "firstParameterValue");
+}
+
+#[tokio::test]
+async fn should_retrieve_nested_parameters_without_envs() {
+    #[build_parameters_struct(path = "/secrets-manager-test-no-prefix-parameters")]
+    struct TestParameters {}
+
+    let mut parameters = TestParameters::try_new().await.unwrap();
+    parameters.refresh().await;
+
+    assert_eq!(parameters.nested_second_parameter, "secondParameterValue");
+}
+
+#[tokio::test]
+async fn should_decrypt_secure_strings_and_split_string_lists() {
+    #[build_parameters_struct(path = "/secrets-manager-test-no-prefix-parameters")]
+    struct TestParameters {}
+
+    let parameters = TestParameters::new().await;
+
+    assert_eq!(parameters.password.as_ref(), "passwordValue");
+    assert_eq!(format!("{:?}", parameters.password), "p****");
+    assert_eq!(parameters.hosts, vec!["first-host", "second-host"]);
+}